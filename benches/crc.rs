@@ -0,0 +1,108 @@
+//! Compare slice-by-8 CRC with the byte-at-a-time table loop
+//!
+//! Run with `cargo bench --bench crc`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use ethqr_gen::crc::{Crc16, Crc16Params, verify_crc, verify_crc_bulk};
+use ethqr_gen::fields::SchemeConfig;
+use ethqr_gen::{QRBuilder, constants};
+
+static TABLE: [u16; 256] = Crc16Params::CCITT_FALSE.table();
+
+/// The byte-at-a-time loop `Crc16::update` used before slice-by-8
+fn crc_bytewise(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for &byte in data {
+        let table_index = ((crc >> 8) ^ u16::from(byte)) & 0xFF;
+        crc = (crc << 8) ^ TABLE[table_index as usize];
+    }
+    crc
+}
+
+fn verify_bytewise(payload: &str) -> bool {
+    let Some(body_len) = payload.len().checked_sub(4) else {
+        return false;
+    };
+    let crc = crc_bytewise(&payload.as_bytes()[..body_len]);
+    payload[body_len..].eq_ignore_ascii_case(&format!("{crc:04X}"))
+}
+
+fn payloads(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            QRBuilder::new()
+                .merchant_name("Coffee Shop")
+                .merchant_city("Addis Ababa")
+                .merchant_category_code("5812")
+                .add_scheme(SchemeConfig::ips_et(
+                    "0123456789ABCDEF0123456789ABCDEF",
+                    "CBETETAA",
+                    "1000123456789",
+                ))
+                .transaction_amount(format!("{}.00", i % 10_000))
+                .build_payload()
+                .unwrap()
+        })
+        .collect()
+}
+
+fn bench_checksum(c: &mut Criterion) {
+    let data: Vec<u8> = (0..=255u8).cycle().take(constants::MAX_QR_LENGTH).collect();
+    let mut group = c.benchmark_group("crc16");
+
+    for len in [64, 160, constants::MAX_QR_LENGTH] {
+        let input = &data[..len];
+        assert_eq!(crc_bytewise(input), {
+            let mut crc = Crc16::new();
+            crc.update(input);
+            crc.value()
+        });
+
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("bytewise", len), input, |b, input| {
+            b.iter(|| crc_bytewise(black_box(input)));
+        });
+        group.bench_with_input(BenchmarkId::new("slice_by_8", len), input, |b, input| {
+            b.iter(|| {
+                let mut crc = Crc16::new();
+                crc.update(black_box(input));
+                crc.value()
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_bulk_verify(c: &mut Criterion) {
+    let payloads = payloads(10_000);
+    assert!(payloads.iter().all(|payload| verify_bytewise(payload)));
+    assert!(verify_crc_bulk(&payloads).is_empty());
+
+    let mut group = c.benchmark_group("verify_10k");
+    group.throughput(Throughput::Elements(payloads.len() as u64));
+    group.bench_function("bytewise", |b| {
+        b.iter(|| {
+            black_box(&payloads)
+                .iter()
+                .filter(|p| !verify_bytewise(p))
+                .count()
+        });
+    });
+    group.bench_function("verify_crc", |b| {
+        b.iter(|| {
+            black_box(&payloads)
+                .iter()
+                .filter(|p| !verify_crc(p))
+                .count()
+        });
+    });
+    group.bench_function("verify_crc_bulk", |b| {
+        b.iter(|| verify_crc_bulk(black_box(&payloads)));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_checksum, bench_bulk_verify);
+criterion_main!(benches);
@@ -0,0 +1,113 @@
+//! Compare the allocation-free encoder and templates with string-based TLV
+//! encoding
+//!
+//! Run with `cargo bench --bench encode --all-features`.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use ethqr_gen::fields::{AdditionalData, SchemeConfig};
+use ethqr_gen::template::QRTemplate;
+use ethqr_gen::{EMVTag, QRBuilder, constants, crc, tags};
+
+fn builder() -> QRBuilder {
+    QRBuilder::new()
+        .merchant_name("Coffee Shop")
+        .merchant_city("Addis Ababa")
+        .merchant_category_code("5812")
+        .add_scheme(SchemeConfig::ips_et(
+            "0123456789ABCDEF0123456789ABCDEF",
+            "CBETETAA",
+            "1000123456789",
+        ))
+        .add_scheme(SchemeConfig::visa("4111111111111111"))
+        .transaction_amount("250.00")
+        .additional_data(
+            AdditionalData::new()
+                .bill_number("INV-2024-001")
+                .terminal_number("POS-07"),
+        )
+}
+
+/// Encoding by collecting one `String` per tag, as the builder used to
+fn encode_with_tags(schemes: &[SchemeConfig], amount: &str, additional: &AdditionalData) -> String {
+    let mut tags = vec![
+        EMVTag::new(
+            tags::PAYLOAD_FORMAT_INDICATOR,
+            constants::PAYLOAD_FORMAT_INDICATOR,
+        ),
+        EMVTag::new(tags::POINT_OF_INITIATION, constants::DYNAMIC_QR_POI),
+    ];
+    tags.extend(schemes.iter().map(|scheme| scheme.encode().unwrap()));
+    tags.push(EMVTag::new(tags::MERCHANT_CATEGORY_CODE, "5812"));
+    tags.push(EMVTag::new(
+        tags::TRANSACTION_CURRENCY,
+        constants::ETB_CURRENCY_CODE,
+    ));
+    tags.push(EMVTag::new(tags::TRANSACTION_AMOUNT, amount));
+    tags.push(EMVTag::new(
+        tags::COUNTRY_CODE,
+        constants::ETHIOPIA_COUNTRY_CODE,
+    ));
+    tags.push(EMVTag::new(tags::MERCHANT_NAME, "Coffee Shop"));
+    tags.push(EMVTag::new(tags::MERCHANT_CITY, "Addis Ababa"));
+    tags.extend(additional.encode());
+
+    let payload = tags.iter().map(EMVTag::encode).collect::<String>();
+    let crc = crc::calculate_crc16(&format!("{payload}6304"));
+    format!("{payload}6304{crc}")
+}
+
+fn bench_encode(c: &mut Criterion) {
+    let builder = builder();
+    let schemes = [
+        SchemeConfig::ips_et(
+            "0123456789ABCDEF0123456789ABCDEF",
+            "CBETETAA",
+            "1000123456789",
+        ),
+        SchemeConfig::visa("4111111111111111"),
+    ];
+    let additional = AdditionalData::new()
+        .bill_number("INV-2024-001")
+        .terminal_number("POS-07");
+    assert_eq!(
+        encode_with_tags(&schemes, "250.00", &additional),
+        builder.build_payload().unwrap()
+    );
+
+    let mut group = c.benchmark_group("encode");
+    group.bench_function("emv_tags", |b| {
+        b.iter(|| encode_with_tags(black_box(&schemes), black_box("250.00"), &additional));
+    });
+    group.bench_function("build_payload", |b| {
+        b.iter(|| black_box(&builder).build_payload().unwrap());
+    });
+    group.bench_function("encode_into", |b| {
+        let mut buf = [0u8; constants::MAX_QR_LENGTH];
+        b.iter(|| black_box(&builder).encode_into(&mut buf).unwrap().len());
+    });
+    group.bench_function("template_stamp", |b| {
+        let template = QRTemplate::new(&builder).unwrap();
+        let mut buf = [0u8; constants::MAX_QR_LENGTH];
+        assert_eq!(
+            template.stamp("250.00").encode_into(&mut buf).unwrap(),
+            builder.build_payload().unwrap()
+        );
+        b.iter(|| {
+            template
+                .stamp(black_box("250.00"))
+                .encode_into(&mut buf)
+                .unwrap()
+                .len()
+        });
+    });
+    #[cfg(feature = "heapless")]
+    group.bench_function("encode_heapless", |b| {
+        b.iter(|| black_box(&builder).encode_heapless().unwrap());
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_encode);
+criterion_main!(benches);
@@ -7,6 +7,7 @@
 
 use ethqr_gen::{
     QRBuilder,
+    export::{ImageEncoder, ImageFormat},
     fields::{AdditionalData, SchemeConfig},
 };
 
@@ -33,5 +34,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     qr_image.save("/tmp/qr_image.png")?;
     println!("Default size QR image saved as: /tmp/qr_image.png");
 
+    // Encode directly to bytes, e.g. for an HTTP response body
+    let png_bytes = qr_builder.build_image_bytes(&ImageEncoder::new(ImageFormat::Png).dpi(300))?;
+    println!("Encoded {} bytes of PNG in memory", png_bytes.len());
+
     Ok(())
 }
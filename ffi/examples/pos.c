@@ -0,0 +1,37 @@
+/*
+ * Build a dynamic payment payload from C.
+ *
+ *   cargo build -p ethqr-ffi --release
+ *   cc ffi/examples/pos.c -Iffi/include -Ltarget/release -lethqr -o pos
+ *   LD_LIBRARY_PATH=target/release ./pos
+ */
+#include <stdio.h>
+
+#include "ethqr.h"
+
+int main(void) {
+    EthqrBuilder *builder = ethqr_builder_new();
+    char payload[ETHQR_MAX_PAYLOAD_LEN + 1];
+    size_t length = 0;
+    EthqrStatus status;
+
+    ethqr_builder_merchant_name(builder, "Coffee Shop");
+    ethqr_builder_merchant_city(builder, "Addis Ababa");
+    ethqr_builder_merchant_category_code(builder, "5812");
+    ethqr_builder_add_ips_et(builder, "581b314e257f41bfbbdc6384daa31d16", "CBETETAA",
+                             "10000171234567890");
+    ethqr_builder_transaction_amount(builder, "120.00");
+    ethqr_builder_additional_data(builder, ETHQR_ADDITIONAL_FIELD_BILL_NUMBER, "INV-001");
+    ethqr_builder_additional_data(builder, ETHQR_ADDITIONAL_FIELD_TERMINAL_NUMBER, "POS-7");
+
+    status = ethqr_builder_build_payload(builder, payload, sizeof payload, &length);
+    ethqr_builder_free(builder);
+
+    if (status != ETHQR_STATUS_OK) {
+        fprintf(stderr, "error %d: %s\n", status, ethqr_last_error_message());
+        return 1;
+    }
+
+    printf("%s\n", payload);
+    return 0;
+}
@@ -0,0 +1,224 @@
+/* ethqr-gen C API. Generated by cbindgen from ffi/src/lib.rs; do not edit. */
+
+#ifndef ETHQR_H
+#define ETHQR_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Largest payload length in bytes, excluding the NUL terminator
+ */
+#define ETHQR_MAX_PAYLOAD_LEN 512
+
+/**
+ * Result of an FFI call
+ *
+ * Values from 10 upwards correspond to the variants of `QRError`.
+ */
+typedef enum {
+  ETHQR_STATUS_OK = 0,
+  /**
+   * A required pointer argument was NULL
+   */
+  ETHQR_STATUS_NULL_POINTER = 1,
+  /**
+   * A string argument was not valid UTF-8
+   */
+  ETHQR_STATUS_INVALID_UTF8 = 2,
+  /**
+   * The output buffer cannot hold the result and its NUL terminator
+   */
+  ETHQR_STATUS_BUFFER_TOO_SMALL = 3,
+  ETHQR_STATUS_INVALID_FORMAT = 10,
+  ETHQR_STATUS_INVALID_CRC = 11,
+  ETHQR_STATUS_MISSING_FIELD = 12,
+  ETHQR_STATUS_INVALID_VALUE = 13,
+  ETHQR_STATUS_VALUE_TOO_LONG = 14,
+  ETHQR_STATUS_UNSUPPORTED_SCHEME = 15,
+  ETHQR_STATUS_PAYLOAD_TOO_LONG = 16,
+  ETHQR_STATUS_BUILDER_ERROR = 17,
+  ETHQR_STATUS_VALIDATION_ERROR = 18,
+  ETHQR_STATUS_QR_CODE_ERROR = 19,
+  ETHQR_STATUS_IMAGE_ERROR = 20,
+  ETHQR_STATUS_IO_ERROR = 21,
+  ETHQR_STATUS_INSUFFICIENT_CONTRAST = 22,
+  ETHQR_STATUS_EXPIRED = 23,
+  ETHQR_STATUS_NOT_YET_VALID = 24,
+} EthqrStatus;
+
+/**
+ * Additional data fields (tag 62), numbered by their sub-tag ID
+ */
+typedef enum {
+  ETHQR_ADDITIONAL_FIELD_BILL_NUMBER = 1,
+  ETHQR_ADDITIONAL_FIELD_MOBILE_NUMBER = 2,
+  ETHQR_ADDITIONAL_FIELD_STORE_LABEL = 3,
+  ETHQR_ADDITIONAL_FIELD_LOYALTY_NUMBER = 4,
+  ETHQR_ADDITIONAL_FIELD_REFERENCE_LABEL = 5,
+  ETHQR_ADDITIONAL_FIELD_CUSTOMER_LABEL = 6,
+  ETHQR_ADDITIONAL_FIELD_TERMINAL_NUMBER = 7,
+  ETHQR_ADDITIONAL_FIELD_PURPOSE = 8,
+  ETHQR_ADDITIONAL_FIELD_ADDITIONAL_CUSTOMER_DATA = 9,
+  ETHQR_ADDITIONAL_FIELD_MERCHANT_TAX_ID = 10,
+  ETHQR_ADDITIONAL_FIELD_MERCHANT_CHANNEL = 11,
+  ETHQR_ADDITIONAL_FIELD_DUE_DATE = 50,
+  ETHQR_ADDITIONAL_FIELD_AMOUNT_AFTER_DUE_DATE = 51,
+} EthqrAdditionalField;
+
+/**
+ * Opaque QR builder handle
+ */
+typedef struct EthqrBuilder EthqrBuilder;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Create a new builder; release it with [`ethqr_builder_free`]
+ */
+EthqrBuilder *ethqr_builder_new(void);
+
+/**
+ * Release a builder. Passing NULL is a no-op.
+ *
+ * # Safety
+ * `builder` must be NULL or a handle from [`ethqr_builder_new`] that has not
+ * been freed.
+ */
+void ethqr_builder_free(EthqrBuilder *builder);
+
+/**
+ * Set merchant name (tag 59)
+ *
+ * # Safety
+ * `builder` must be a live handle and `name` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_merchant_name(EthqrBuilder *builder, const char *name);
+
+/**
+ * Set merchant city (tag 60)
+ *
+ * # Safety
+ * `builder` must be a live handle and `city` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_merchant_city(EthqrBuilder *builder, const char *city);
+
+/**
+ * Set merchant category code (tag 52)
+ *
+ * # Safety
+ * `builder` must be a live handle and `code` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_merchant_category_code(EthqrBuilder *builder, const char *code);
+
+/**
+ * Set transaction amount, making the QR code dynamic (tag 54)
+ *
+ * # Safety
+ * `builder` must be a live handle and `amount` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_transaction_amount(EthqrBuilder *builder, const char *amount);
+
+/**
+ * Set transaction context (tag 80)
+ *
+ * # Safety
+ * `builder` must be a live handle and `context` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_transaction_context(EthqrBuilder *builder, const char *context);
+
+/**
+ * Add a Visa merchant account (tag 02)
+ *
+ * # Safety
+ * `builder` must be a live handle and `account_info` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_add_visa(EthqrBuilder *builder, const char *account_info);
+
+/**
+ * Add a Mastercard merchant account (tag 04)
+ *
+ * # Safety
+ * `builder` must be a live handle and `account_info` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_add_mastercard(EthqrBuilder *builder, const char *account_info);
+
+/**
+ * Add a UnionPay merchant account (tag 15)
+ *
+ * # Safety
+ * `builder` must be a live handle and `account_info` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_add_unionpay(EthqrBuilder *builder, const char *account_info);
+
+/**
+ * Add an IPS ET merchant account (tag 28)
+ *
+ * # Safety
+ * `builder` must be a live handle and the other arguments NUL-terminated
+ * strings.
+ */
+EthqrStatus ethqr_builder_add_ips_et(EthqrBuilder *builder,
+                                     const char *guid,
+                                     const char *bic,
+                                     const char *account);
+
+/**
+ * Set an additional data field (tag 62)
+ *
+ * `field` is one of the [`EthqrAdditionalField`] values.
+ *
+ * # Safety
+ * `builder` must be a live handle and `value` a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_additional_data(EthqrBuilder *builder, uint32_t field, const char *value);
+
+/**
+ * Set merchant information in an alternate language (tag 64)
+ *
+ * `city` may be NULL.
+ *
+ * # Safety
+ * `builder` must be a live handle, `language` and `name` NUL-terminated
+ * strings, and `city` NULL or a NUL-terminated string.
+ */
+EthqrStatus ethqr_builder_language_template(EthqrBuilder *builder,
+                                            const char *language,
+                                            const char *name,
+                                            const char *city);
+
+/**
+ * Build the payload into `buffer` as a NUL-terminated string
+ *
+ * The payload length, excluding the terminator, is stored in `out_len` when
+ * it is not NULL, including on [`EthqrStatus::BufferTooSmall`], so callers
+ * can size the buffer by first passing a NULL `buffer` with `buffer_len` 0.
+ * A buffer of `ETHQR_MAX_PAYLOAD_LEN + 1` bytes is always large enough.
+ *
+ * # Safety
+ * `builder` must be a live handle, `buffer` must be valid for `buffer_len`
+ * bytes (or NULL when `buffer_len` is 0) and `out_len` NULL or writable.
+ */
+EthqrStatus ethqr_builder_build_payload(const EthqrBuilder *builder,
+                                        char *buffer,
+                                        size_t buffer_len,
+                                        size_t *out_len);
+
+/**
+ * Message describing the most recent failure on the calling thread
+ *
+ * Returns NULL if no call has failed yet. The string remains valid until the
+ * next failing call on the same thread.
+ */
+const char *ethqr_last_error_message(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ETHQR_H */
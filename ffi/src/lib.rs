@@ -0,0 +1,606 @@
+//! C ABI for `ethqr-gen`
+//!
+//! Builders are exposed as opaque [`EthqrBuilder`] handles. Every function
+//! returns an [`EthqrStatus`]; on failure a description is available from
+//! [`ethqr_last_error_message`] on the same thread. The C header is generated
+//! into `ffi/include/ethqr.h` by the build script.
+//!
+//! ```c
+//! EthqrBuilder *builder = ethqr_builder_new();
+//! ethqr_builder_merchant_name(builder, "Coffee Shop");
+//! ethqr_builder_merchant_city(builder, "Addis Ababa");
+//! ethqr_builder_merchant_category_code(builder, "5812");
+//! ethqr_builder_add_visa(builder, "4111111111111111");
+//!
+//! char payload[ETHQR_MAX_PAYLOAD_LEN + 1];
+//! size_t length;
+//! if (ethqr_builder_build_payload(builder, payload, sizeof payload, &length) != ETHQR_STATUS_OK) {
+//!     fprintf(stderr, "%s\n", ethqr_last_error_message());
+//! }
+//! ethqr_builder_free(builder);
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+use ethqr_gen::QRBuilder;
+use ethqr_gen::error::QRError;
+use ethqr_gen::fields::{AdditionalData, LanguageTemplate, SchemeConfig};
+
+/// Largest payload length in bytes, excluding the NUL terminator
+pub const ETHQR_MAX_PAYLOAD_LEN: usize = 512;
+
+/// Result of an FFI call
+///
+/// Values from 10 upwards correspond to the variants of `QRError`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EthqrStatus {
+    Ok = 0,
+    /// A required pointer argument was NULL
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The output buffer cannot hold the result and its NUL terminator
+    BufferTooSmall = 3,
+    InvalidFormat = 10,
+    InvalidCrc = 11,
+    MissingField = 12,
+    InvalidValue = 13,
+    ValueTooLong = 14,
+    UnsupportedScheme = 15,
+    PayloadTooLong = 16,
+    BuilderError = 17,
+    ValidationError = 18,
+    QrCodeError = 19,
+    ImageError = 20,
+    IoError = 21,
+    InsufficientContrast = 22,
+    Expired = 23,
+    NotYetValid = 24,
+}
+
+impl From<&QRError> for EthqrStatus {
+    fn from(err: &QRError) -> Self {
+        match err {
+            QRError::InvalidFormat { .. } => EthqrStatus::InvalidFormat,
+            QRError::InvalidCRC | QRError::CrcCheckFailed { .. } => EthqrStatus::InvalidCrc,
+            QRError::MissingField { .. } => EthqrStatus::MissingField,
+            QRError::InvalidValue { .. } => EthqrStatus::InvalidValue,
+            QRError::ValueTooLong { .. } => EthqrStatus::ValueTooLong,
+            QRError::UnsupportedScheme { .. } => EthqrStatus::UnsupportedScheme,
+            QRError::PayloadTooLong { .. } => EthqrStatus::PayloadTooLong,
+            QRError::BuilderError { .. } => EthqrStatus::BuilderError,
+            QRError::ValidationError { .. } => EthqrStatus::ValidationError,
+            QRError::QRCodeError { .. } => EthqrStatus::QrCodeError,
+            QRError::ImageError { .. } => EthqrStatus::ImageError,
+            QRError::IoError { .. } => EthqrStatus::IoError,
+            QRError::InsufficientContrast { .. } => EthqrStatus::InsufficientContrast,
+            QRError::Expired { .. } => EthqrStatus::Expired,
+            QRError::NotYetValid { .. } => EthqrStatus::NotYetValid,
+        }
+    }
+}
+
+/// Additional data fields (tag 62), numbered by their sub-tag ID
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EthqrAdditionalField {
+    BillNumber = 1,
+    MobileNumber = 2,
+    StoreLabel = 3,
+    LoyaltyNumber = 4,
+    ReferenceLabel = 5,
+    CustomerLabel = 6,
+    TerminalNumber = 7,
+    Purpose = 8,
+    AdditionalCustomerData = 9,
+    MerchantTaxId = 10,
+    MerchantChannel = 11,
+    DueDate = 50,
+    AmountAfterDueDate = 51,
+}
+
+/// Opaque QR builder handle
+pub struct EthqrBuilder {
+    builder: QRBuilder,
+    additional_data: AdditionalData,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn fail(status: EthqrStatus, message: impl Into<String>) -> EthqrStatus {
+    // Interior NULs cannot occur in our messages, but never lose the error
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    status
+}
+
+fn fail_with(err: &QRError) -> EthqrStatus {
+    fail(EthqrStatus::from(err), err.to_string())
+}
+
+/// Borrow a NUL-terminated UTF-8 string argument
+unsafe fn str_arg<'a>(value: *const c_char, name: &str) -> Result<&'a str, EthqrStatus> {
+    if value.is_null() {
+        return Err(fail(
+            EthqrStatus::NullPointer,
+            format!("{name} must not be NULL"),
+        ));
+    }
+    // SAFETY: the caller guarantees a valid NUL-terminated string
+    unsafe { CStr::from_ptr(value) }.to_str().map_err(|_| {
+        fail(
+            EthqrStatus::InvalidUtf8,
+            format!("{name} is not valid UTF-8"),
+        )
+    })
+}
+
+/// Apply a consuming builder setter through a handle
+unsafe fn update(
+    builder: *mut EthqrBuilder,
+    setter: impl FnOnce(&mut EthqrBuilder) -> Result<(), EthqrStatus>,
+) -> EthqrStatus {
+    // SAFETY: the caller guarantees the handle came from `ethqr_builder_new`
+    match unsafe { builder.as_mut() } {
+        Some(handle) => setter(handle).err().unwrap_or(EthqrStatus::Ok),
+        None => fail(EthqrStatus::NullPointer, "builder must not be NULL"),
+    }
+}
+
+fn set(handle: &mut EthqrBuilder, setter: impl FnOnce(QRBuilder) -> QRBuilder) {
+    handle.builder = setter(std::mem::take(&mut handle.builder));
+}
+
+/// Create a new builder; release it with [`ethqr_builder_free`]
+#[unsafe(no_mangle)]
+pub extern "C" fn ethqr_builder_new() -> *mut EthqrBuilder {
+    Box::into_raw(Box::new(EthqrBuilder {
+        builder: QRBuilder::new(),
+        additional_data: AdditionalData::new(),
+    }))
+}
+
+/// Release a builder. Passing NULL is a no-op.
+///
+/// # Safety
+/// `builder` must be NULL or a handle from [`ethqr_builder_new`] that has not
+/// been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_free(builder: *mut EthqrBuilder) {
+    if !builder.is_null() {
+        // SAFETY: ownership is returned by the caller
+        drop(unsafe { Box::from_raw(builder) });
+    }
+}
+
+/// Set merchant name (tag 59)
+///
+/// # Safety
+/// `builder` must be a live handle and `name` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_merchant_name(
+    builder: *mut EthqrBuilder,
+    name: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let name = str_arg(name, "name")?;
+            set(handle, |b| b.merchant_name(name));
+            Ok(())
+        })
+    }
+}
+
+/// Set merchant city (tag 60)
+///
+/// # Safety
+/// `builder` must be a live handle and `city` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_merchant_city(
+    builder: *mut EthqrBuilder,
+    city: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let city = str_arg(city, "city")?;
+            set(handle, |b| b.merchant_city(city));
+            Ok(())
+        })
+    }
+}
+
+/// Set merchant category code (tag 52)
+///
+/// # Safety
+/// `builder` must be a live handle and `code` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_merchant_category_code(
+    builder: *mut EthqrBuilder,
+    code: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let code = str_arg(code, "code")?;
+            set(handle, |b| b.merchant_category_code(code));
+            Ok(())
+        })
+    }
+}
+
+/// Set transaction amount, making the QR code dynamic (tag 54)
+///
+/// # Safety
+/// `builder` must be a live handle and `amount` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_transaction_amount(
+    builder: *mut EthqrBuilder,
+    amount: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let amount = str_arg(amount, "amount")?;
+            set(handle, |b| b.transaction_amount(amount));
+            Ok(())
+        })
+    }
+}
+
+/// Set transaction context (tag 80)
+///
+/// # Safety
+/// `builder` must be a live handle and `context` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_transaction_context(
+    builder: *mut EthqrBuilder,
+    context: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let context = str_arg(context, "context")?;
+            set(handle, |b| b.transaction_context(context));
+            Ok(())
+        })
+    }
+}
+
+/// Add a Visa merchant account (tag 02)
+///
+/// # Safety
+/// `builder` must be a live handle and `account_info` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_add_visa(
+    builder: *mut EthqrBuilder,
+    account_info: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let account_info = str_arg(account_info, "account_info")?;
+            set(handle, |b| b.add_scheme(SchemeConfig::visa(account_info)));
+            Ok(())
+        })
+    }
+}
+
+/// Add a Mastercard merchant account (tag 04)
+///
+/// # Safety
+/// `builder` must be a live handle and `account_info` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_add_mastercard(
+    builder: *mut EthqrBuilder,
+    account_info: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let account_info = str_arg(account_info, "account_info")?;
+            set(handle, |b| {
+                b.add_scheme(SchemeConfig::mastercard(account_info))
+            });
+            Ok(())
+        })
+    }
+}
+
+/// Add a UnionPay merchant account (tag 15)
+///
+/// # Safety
+/// `builder` must be a live handle and `account_info` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_add_unionpay(
+    builder: *mut EthqrBuilder,
+    account_info: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let account_info = str_arg(account_info, "account_info")?.to_string();
+            set(handle, |b| {
+                b.add_scheme(SchemeConfig::UnionPay { account_info })
+            });
+            Ok(())
+        })
+    }
+}
+
+/// Add an IPS ET merchant account (tag 28)
+///
+/// # Safety
+/// `builder` must be a live handle and the other arguments NUL-terminated
+/// strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_add_ips_et(
+    builder: *mut EthqrBuilder,
+    guid: *const c_char,
+    bic: *const c_char,
+    account: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let guid = str_arg(guid, "guid")?;
+            let bic = str_arg(bic, "bic")?;
+            let account = str_arg(account, "account")?;
+            set(handle, |b| {
+                b.add_scheme(SchemeConfig::ips_et(guid, bic, account))
+            });
+            Ok(())
+        })
+    }
+}
+
+/// Set an additional data field (tag 62)
+///
+/// `field` is one of the [`EthqrAdditionalField`] values.
+///
+/// # Safety
+/// `builder` must be a live handle and `value` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_additional_data(
+    builder: *mut EthqrBuilder,
+    field: u32,
+    value: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let value = Some(str_arg(value, "value")?.to_string());
+            let data = &mut handle.additional_data;
+            let slot = match field {
+                1 => &mut data.bill_number,
+                2 => &mut data.mobile_number,
+                3 => &mut data.store_label,
+                4 => &mut data.loyalty_number,
+                5 => &mut data.reference_label,
+                6 => &mut data.customer_label,
+                7 => &mut data.terminal_number,
+                8 => &mut data.purpose,
+                9 => &mut data.additional_customer_data,
+                10 => &mut data.merchant_tax_id,
+                11 => &mut data.merchant_channel,
+                50 => &mut data.due_date,
+                51 => &mut data.amount_after_due_date,
+                _ => {
+                    return Err(fail_with(&QRError::InvalidValue {
+                        field: "additional_data_field".to_string(),
+                        value: field.to_string(),
+                    }));
+                }
+            };
+            *slot = value;
+            Ok(())
+        })
+    }
+}
+
+/// Set merchant information in an alternate language (tag 64)
+///
+/// `city` may be NULL.
+///
+/// # Safety
+/// `builder` must be a live handle, `language` and `name` NUL-terminated
+/// strings, and `city` NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_language_template(
+    builder: *mut EthqrBuilder,
+    language: *const c_char,
+    name: *const c_char,
+    city: *const c_char,
+) -> EthqrStatus {
+    unsafe {
+        update(builder, |handle| {
+            let mut template =
+                LanguageTemplate::new(str_arg(language, "language")?, str_arg(name, "name")?);
+            if !city.is_null() {
+                template = template.merchant_city(str_arg(city, "city")?);
+            }
+            set(handle, |b| b.language_template(template));
+            Ok(())
+        })
+    }
+}
+
+/// Build the payload into `buffer` as a NUL-terminated string
+///
+/// The payload length, excluding the terminator, is stored in `out_len` when
+/// it is not NULL, including on [`EthqrStatus::BufferTooSmall`], so callers
+/// can size the buffer by first passing a NULL `buffer` with `buffer_len` 0.
+/// A buffer of `ETHQR_MAX_PAYLOAD_LEN + 1` bytes is always large enough.
+///
+/// # Safety
+/// `builder` must be a live handle, `buffer` must be valid for `buffer_len`
+/// bytes (or NULL when `buffer_len` is 0) and `out_len` NULL or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ethqr_builder_build_payload(
+    builder: *const EthqrBuilder,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+) -> EthqrStatus {
+    // SAFETY: the caller guarantees the handle came from `ethqr_builder_new`
+    let Some(handle) = (unsafe { builder.as_ref() }) else {
+        return fail(EthqrStatus::NullPointer, "builder must not be NULL");
+    };
+
+    let mut qr_builder = handle.builder.clone();
+    if handle.additional_data != AdditionalData::default() {
+        qr_builder = qr_builder.additional_data(handle.additional_data.clone());
+    }
+
+    let payload = match qr_builder.build_payload() {
+        Ok(payload) => payload,
+        Err(e) => return fail_with(&e),
+    };
+
+    if !out_len.is_null() {
+        // SAFETY: checked for NULL; the caller guarantees it is writable
+        unsafe { *out_len = payload.len() };
+    }
+
+    if buffer.is_null() || buffer_len < payload.len() + 1 {
+        return fail(
+            EthqrStatus::BufferTooSmall,
+            format!(
+                "payload needs {} bytes, buffer has {buffer_len}",
+                payload.len() + 1
+            ),
+        );
+    }
+
+    // SAFETY: the buffer holds at least `payload.len() + 1` bytes
+    unsafe {
+        ptr::copy_nonoverlapping(payload.as_ptr().cast(), buffer, payload.len());
+        *buffer.add(payload.len()) = 0;
+    }
+
+    EthqrStatus::Ok
+}
+
+/// Message describing the most recent failure on the calling thread
+///
+/// Returns NULL if no call has failed yet. The string remains valid until the
+/// next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn ethqr_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(value: &str) -> CString {
+        CString::new(value).unwrap()
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(ethqr_last_error_message()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_build_payload_matches_builder() {
+        let builder = ethqr_builder_new();
+        unsafe {
+            ethqr_builder_merchant_name(builder, c("Restaurant").as_ptr());
+            ethqr_builder_merchant_city(builder, c("Dire Dawa").as_ptr());
+            ethqr_builder_merchant_category_code(builder, c("5812").as_ptr());
+            ethqr_builder_add_ips_et(
+                builder,
+                c("581b314e257f41bfbbdc6384daa31d16").as_ptr(),
+                c("CBETETAA").as_ptr(),
+                c("10000171234567890").as_ptr(),
+            );
+            ethqr_builder_transaction_amount(builder, c("50.00").as_ptr());
+            let status = ethqr_builder_additional_data(
+                builder,
+                EthqrAdditionalField::BillNumber as u32,
+                c("INV-001").as_ptr(),
+            );
+            assert_eq!(status, EthqrStatus::Ok);
+        }
+
+        let expected = QRBuilder::new()
+            .merchant_name("Restaurant")
+            .merchant_city("Dire Dawa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::ips_et(
+                "581b314e257f41bfbbdc6384daa31d16",
+                "CBETETAA",
+                "10000171234567890",
+            ))
+            .transaction_amount("50.00")
+            .additional_data(AdditionalData::new().bill_number("INV-001"))
+            .build_payload()
+            .unwrap();
+
+        let mut length = 0;
+        let mut buffer = [0 as c_char; ETHQR_MAX_PAYLOAD_LEN + 1];
+        let status = unsafe {
+            ethqr_builder_build_payload(builder, buffer.as_mut_ptr(), buffer.len(), &mut length)
+        };
+
+        assert_eq!(status, EthqrStatus::Ok);
+        assert_eq!(length, expected.len());
+        assert_eq!(
+            unsafe { CStr::from_ptr(buffer.as_ptr()) }.to_str().unwrap(),
+            expected
+        );
+        unsafe { ethqr_builder_free(builder) };
+    }
+
+    #[test]
+    fn test_buffer_too_small_reports_length() {
+        let builder = ethqr_builder_new();
+        unsafe {
+            ethqr_builder_merchant_name(builder, c("Shop").as_ptr());
+            ethqr_builder_merchant_city(builder, c("Adama").as_ptr());
+            ethqr_builder_merchant_category_code(builder, c("5411").as_ptr());
+            ethqr_builder_add_visa(builder, c("4111111111111111").as_ptr());
+        }
+
+        let mut length = 0;
+        let status =
+            unsafe { ethqr_builder_build_payload(builder, ptr::null_mut(), 0, &mut length) };
+        assert_eq!(status, EthqrStatus::BufferTooSmall);
+        assert!(length > 0);
+
+        let mut buffer = vec![0 as c_char; length];
+        let status = unsafe {
+            ethqr_builder_build_payload(builder, buffer.as_mut_ptr(), buffer.len(), ptr::null_mut())
+        };
+        assert_eq!(status, EthqrStatus::BufferTooSmall);
+        unsafe { ethqr_builder_free(builder) };
+    }
+
+    #[test]
+    fn test_errors_map_to_status() {
+        let builder = ethqr_builder_new();
+        unsafe {
+            let status = ethqr_builder_merchant_name(builder, ptr::null());
+            assert_eq!(status, EthqrStatus::NullPointer);
+
+            let status = ethqr_builder_additional_data(builder, 99, c("x").as_ptr());
+            assert_eq!(status, EthqrStatus::InvalidValue);
+
+            ethqr_builder_merchant_name(builder, c("A merchant name that is too long").as_ptr());
+            let mut buffer = [0 as c_char; 16];
+            let status = ethqr_builder_build_payload(
+                builder,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                ptr::null_mut(),
+            );
+            assert_eq!(status, EthqrStatus::ValueTooLong);
+            assert!(last_error().contains("name"));
+
+            ethqr_builder_free(builder);
+            assert_eq!(
+                ethqr_builder_merchant_city(ptr::null_mut(), c("x").as_ptr()),
+                EthqrStatus::NullPointer
+            );
+        }
+    }
+}
@@ -0,0 +1,57 @@
+"""EMVCo-compliant QR payment codes for the Ethiopian Interoperable QR Standard."""
+
+from ethqr_gen._ethqr import (
+    AdditionalData,
+    BuilderError,
+    ExpiredError,
+    ImageError,
+    InsufficientContrastError,
+    InvalidCRCError,
+    InvalidFormatError,
+    InvalidValueError,
+    IoError,
+    LanguageTemplate,
+    MissingFieldError,
+    NotYetValidError,
+    ParsedQR,
+    PayloadTooLongError,
+    QRBuilder,
+    QRCodeError,
+    QRError,
+    SchemeConfig,
+    UnsupportedSchemeError,
+    ValidationError,
+    Validity,
+    ValueTooLongError,
+    calculate_crc,
+    parse,
+    verify_crc,
+)
+
+__all__ = [
+    "AdditionalData",
+    "BuilderError",
+    "ExpiredError",
+    "ImageError",
+    "InsufficientContrastError",
+    "InvalidCRCError",
+    "InvalidFormatError",
+    "InvalidValueError",
+    "IoError",
+    "LanguageTemplate",
+    "MissingFieldError",
+    "NotYetValidError",
+    "ParsedQR",
+    "PayloadTooLongError",
+    "QRBuilder",
+    "QRCodeError",
+    "QRError",
+    "SchemeConfig",
+    "UnsupportedSchemeError",
+    "ValidationError",
+    "Validity",
+    "ValueTooLongError",
+    "calculate_crc",
+    "parse",
+    "verify_crc",
+]
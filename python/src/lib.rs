@@ -0,0 +1,633 @@
+//! Python bindings for `ethqr-gen`
+//!
+//! Built into the `ethqr_gen` Python package with maturin:
+//!
+//! ```sh
+//! cd python
+//! maturin build --release
+//! ```
+//!
+//! Errors are raised as subclasses of `ethqr_gen.QRError` (itself a
+//! `ValueError`), one per `QRError` variant.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use ethqr_gen::error::QRError as CoreError;
+use ethqr_gen::fields::{
+    AdditionalData, LanguageTemplate, PointOfInitiation, SchemeConfig, Validity,
+};
+use ethqr_gen::{QRBuilder, crc, parser};
+
+create_exception!(
+    ethqr_gen,
+    QRError,
+    PyValueError,
+    "Base class for all ethqr-gen errors"
+);
+create_exception!(ethqr_gen, InvalidFormatError, QRError);
+create_exception!(ethqr_gen, InvalidCRCError, QRError);
+create_exception!(ethqr_gen, MissingFieldError, QRError);
+create_exception!(ethqr_gen, InvalidValueError, QRError);
+create_exception!(ethqr_gen, ValueTooLongError, QRError);
+create_exception!(ethqr_gen, UnsupportedSchemeError, QRError);
+create_exception!(ethqr_gen, PayloadTooLongError, QRError);
+create_exception!(ethqr_gen, BuilderError, QRError);
+create_exception!(ethqr_gen, ValidationError, QRError);
+create_exception!(ethqr_gen, QRCodeError, QRError);
+create_exception!(ethqr_gen, ImageError, QRError);
+create_exception!(ethqr_gen, IoError, QRError);
+create_exception!(ethqr_gen, InsufficientContrastError, QRError);
+create_exception!(ethqr_gen, ExpiredError, QRError);
+create_exception!(ethqr_gen, NotYetValidError, QRError);
+
+fn to_py_err(err: CoreError) -> PyErr {
+    let message = err.to_string();
+    match err {
+        CoreError::InvalidFormat { .. } => InvalidFormatError::new_err(message),
+        CoreError::InvalidCRC | CoreError::CrcCheckFailed { .. } => {
+            InvalidCRCError::new_err(message)
+        }
+        CoreError::MissingField { .. } => MissingFieldError::new_err(message),
+        CoreError::InvalidValue { .. } => InvalidValueError::new_err(message),
+        CoreError::ValueTooLong { .. } => ValueTooLongError::new_err(message),
+        CoreError::UnsupportedScheme { .. } => UnsupportedSchemeError::new_err(message),
+        CoreError::PayloadTooLong { .. } => PayloadTooLongError::new_err(message),
+        CoreError::BuilderError { .. } => BuilderError::new_err(message),
+        CoreError::ValidationError { .. } => ValidationError::new_err(message),
+        CoreError::QRCodeError { .. } => QRCodeError::new_err(message),
+        CoreError::ImageError { .. } => ImageError::new_err(message),
+        CoreError::IoError { .. } => IoError::new_err(message),
+        CoreError::InsufficientContrast { .. } => InsufficientContrastError::new_err(message),
+        CoreError::Expired { .. } => ExpiredError::new_err(message),
+        CoreError::NotYetValid { .. } => NotYetValidError::new_err(message),
+    }
+}
+
+/// Payment scheme configuration
+#[pyclass(
+    name = "SchemeConfig",
+    module = "ethqr_gen",
+    frozen,
+    eq,
+    from_py_object
+)]
+#[derive(Clone, PartialEq)]
+struct PySchemeConfig(SchemeConfig);
+
+#[pymethods]
+impl PySchemeConfig {
+    /// Visa merchant account (tag 02)
+    #[staticmethod]
+    fn visa(account_info: &str) -> Self {
+        Self(SchemeConfig::visa(account_info))
+    }
+
+    /// Mastercard merchant account (tag 04)
+    #[staticmethod]
+    fn mastercard(account_info: &str) -> Self {
+        Self(SchemeConfig::mastercard(account_info))
+    }
+
+    /// UnionPay merchant account (tag 15)
+    #[staticmethod]
+    fn unionpay(account_info: &str) -> Self {
+        Self(SchemeConfig::UnionPay {
+            account_info: account_info.to_string(),
+        })
+    }
+
+    /// IPS ET merchant account (tag 28)
+    #[staticmethod]
+    fn ips_et(guid: &str, bic: &str, account: &str) -> Self {
+        Self(SchemeConfig::ips_et(guid, bic, account))
+    }
+
+    /// Scheme name: `visa`, `mastercard`, `unionpay` or `ips_et`
+    #[getter]
+    fn scheme(&self) -> &'static str {
+        match self.0 {
+            SchemeConfig::Visa { .. } => "visa",
+            SchemeConfig::Mastercard { .. } => "mastercard",
+            SchemeConfig::UnionPay { .. } => "unionpay",
+            SchemeConfig::IPSET { .. } => "ips_et",
+        }
+    }
+
+    #[getter]
+    fn tag_id(&self) -> &str {
+        self.0.tag_id()
+    }
+
+    /// Account information for card schemes, `None` for IPS ET
+    #[getter]
+    fn account_info(&self) -> Option<&str> {
+        match self.0 {
+            SchemeConfig::Visa { ref account_info }
+            | SchemeConfig::Mastercard { ref account_info }
+            | SchemeConfig::UnionPay { ref account_info } => Some(account_info),
+            SchemeConfig::IPSET { .. } => None,
+        }
+    }
+
+    #[getter]
+    fn guid(&self) -> Option<&str> {
+        match self.0 {
+            SchemeConfig::IPSET { ref guid, .. } => Some(guid),
+            _ => None,
+        }
+    }
+
+    #[getter]
+    fn bic(&self) -> Option<&str> {
+        match self.0 {
+            SchemeConfig::IPSET { ref bic, .. } => Some(bic),
+            _ => None,
+        }
+    }
+
+    #[getter]
+    fn account(&self) -> Option<&str> {
+        match self.0 {
+            SchemeConfig::IPSET { ref account, .. } => Some(account),
+            _ => None,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        match self.0 {
+            SchemeConfig::IPSET {
+                ref guid,
+                ref bic,
+                ref account,
+            } => format!("SchemeConfig.ips_et({guid:?}, {bic:?}, {account:?})"),
+            _ => format!(
+                "SchemeConfig.{}({:?})",
+                self.scheme(),
+                self.account_info().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Additional data fields (tag 62)
+#[pyclass(name = "AdditionalData", module = "ethqr_gen", eq, from_py_object)]
+#[derive(Clone, Default, PartialEq)]
+struct PyAdditionalData {
+    #[pyo3(get, set)]
+    bill_number: Option<String>,
+    #[pyo3(get, set)]
+    mobile_number: Option<String>,
+    #[pyo3(get, set)]
+    store_label: Option<String>,
+    #[pyo3(get, set)]
+    loyalty_number: Option<String>,
+    #[pyo3(get, set)]
+    reference_label: Option<String>,
+    #[pyo3(get, set)]
+    customer_label: Option<String>,
+    #[pyo3(get, set)]
+    terminal_number: Option<String>,
+    #[pyo3(get, set)]
+    purpose: Option<String>,
+    #[pyo3(get, set)]
+    additional_customer_data: Option<String>,
+    #[pyo3(get, set)]
+    merchant_tax_id: Option<String>,
+    #[pyo3(get, set)]
+    merchant_channel: Option<String>,
+    #[pyo3(get, set)]
+    due_date: Option<String>,
+    #[pyo3(get, set)]
+    amount_after_due_date: Option<String>,
+}
+
+#[pymethods]
+impl PyAdditionalData {
+    #[new]
+    #[pyo3(signature = (
+        *,
+        bill_number = None,
+        mobile_number = None,
+        store_label = None,
+        loyalty_number = None,
+        reference_label = None,
+        customer_label = None,
+        terminal_number = None,
+        purpose = None,
+        additional_customer_data = None,
+        merchant_tax_id = None,
+        merchant_channel = None,
+        due_date = None,
+        amount_after_due_date = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        bill_number: Option<String>,
+        mobile_number: Option<String>,
+        store_label: Option<String>,
+        loyalty_number: Option<String>,
+        reference_label: Option<String>,
+        customer_label: Option<String>,
+        terminal_number: Option<String>,
+        purpose: Option<String>,
+        additional_customer_data: Option<String>,
+        merchant_tax_id: Option<String>,
+        merchant_channel: Option<String>,
+        due_date: Option<String>,
+        amount_after_due_date: Option<String>,
+    ) -> Self {
+        Self {
+            bill_number,
+            mobile_number,
+            store_label,
+            loyalty_number,
+            reference_label,
+            customer_label,
+            terminal_number,
+            purpose,
+            additional_customer_data,
+            merchant_tax_id,
+            merchant_channel,
+            due_date,
+            amount_after_due_date,
+        }
+    }
+}
+
+impl From<PyAdditionalData> for AdditionalData {
+    fn from(data: PyAdditionalData) -> Self {
+        AdditionalData {
+            bill_number: data.bill_number,
+            mobile_number: data.mobile_number,
+            store_label: data.store_label,
+            loyalty_number: data.loyalty_number,
+            reference_label: data.reference_label,
+            customer_label: data.customer_label,
+            terminal_number: data.terminal_number,
+            purpose: data.purpose,
+            additional_customer_data: data.additional_customer_data,
+            merchant_tax_id: data.merchant_tax_id,
+            merchant_channel: data.merchant_channel,
+            due_date: data.due_date,
+            amount_after_due_date: data.amount_after_due_date,
+        }
+    }
+}
+
+impl From<AdditionalData> for PyAdditionalData {
+    fn from(data: AdditionalData) -> Self {
+        PyAdditionalData {
+            bill_number: data.bill_number,
+            mobile_number: data.mobile_number,
+            store_label: data.store_label,
+            loyalty_number: data.loyalty_number,
+            reference_label: data.reference_label,
+            customer_label: data.customer_label,
+            terminal_number: data.terminal_number,
+            purpose: data.purpose,
+            additional_customer_data: data.additional_customer_data,
+            merchant_tax_id: data.merchant_tax_id,
+            merchant_channel: data.merchant_channel,
+            due_date: data.due_date,
+            amount_after_due_date: data.amount_after_due_date,
+        }
+    }
+}
+
+/// Merchant name and city in an alternate language (tag 64)
+#[pyclass(name = "LanguageTemplate", module = "ethqr_gen", eq, from_py_object)]
+#[derive(Clone, PartialEq)]
+struct PyLanguageTemplate {
+    #[pyo3(get, set)]
+    language_preference: String,
+    #[pyo3(get, set)]
+    merchant_name: String,
+    #[pyo3(get, set)]
+    merchant_city: Option<String>,
+}
+
+#[pymethods]
+impl PyLanguageTemplate {
+    #[new]
+    #[pyo3(signature = (language_preference, merchant_name, merchant_city = None))]
+    fn new(
+        language_preference: String,
+        merchant_name: String,
+        merchant_city: Option<String>,
+    ) -> Self {
+        Self {
+            language_preference,
+            merchant_name,
+            merchant_city,
+        }
+    }
+}
+
+impl From<PyLanguageTemplate> for LanguageTemplate {
+    fn from(template: PyLanguageTemplate) -> Self {
+        LanguageTemplate {
+            language_preference: template.language_preference,
+            merchant_name: template.merchant_name,
+            merchant_city: template.merchant_city,
+        }
+    }
+}
+
+impl From<LanguageTemplate> for PyLanguageTemplate {
+    fn from(template: LanguageTemplate) -> Self {
+        PyLanguageTemplate {
+            language_preference: template.language_preference,
+            merchant_name: template.merchant_name,
+            merchant_city: template.merchant_city,
+        }
+    }
+}
+
+/// Window in which a dynamic code may be paid (tag 90), as Unix timestamps
+#[pyclass(name = "Validity", module = "ethqr_gen", eq, from_py_object)]
+#[derive(Clone, PartialEq)]
+struct PyValidity {
+    #[pyo3(get, set)]
+    not_before: Option<u64>,
+    #[pyo3(get, set)]
+    expires_at: u64,
+}
+
+#[pymethods]
+impl PyValidity {
+    #[new]
+    #[pyo3(signature = (expires_at, not_before = None))]
+    fn new(expires_at: u64, not_before: Option<u64>) -> Self {
+        Self {
+            not_before,
+            expires_at,
+        }
+    }
+
+    /// Valid for `seconds` starting at `now`
+    #[staticmethod]
+    fn for_duration(now: u64, seconds: u64) -> Self {
+        Validity::for_duration(now, seconds).into()
+    }
+
+    /// Raise `ExpiredError` or `NotYetValidError` if `now` is outside the window
+    fn check(&self, now: u64) -> PyResult<()> {
+        Validity::from(self.clone()).check(now).map_err(to_py_err)
+    }
+}
+
+impl From<PyValidity> for Validity {
+    fn from(validity: PyValidity) -> Self {
+        Validity {
+            not_before: validity.not_before,
+            expires_at: validity.expires_at,
+        }
+    }
+}
+
+impl From<Validity> for PyValidity {
+    fn from(validity: Validity) -> Self {
+        PyValidity {
+            not_before: validity.not_before,
+            expires_at: validity.expires_at,
+        }
+    }
+}
+
+/// Builder for QR payment payloads
+#[pyclass(name = "QRBuilder", module = "ethqr_gen")]
+struct PyQRBuilder {
+    #[pyo3(get, set)]
+    merchant_name: String,
+    #[pyo3(get, set)]
+    merchant_city: String,
+    #[pyo3(get, set)]
+    merchant_category_code: String,
+    #[pyo3(get, set)]
+    schemes: Vec<PySchemeConfig>,
+    #[pyo3(get, set)]
+    transaction_amount: Option<String>,
+    #[pyo3(get, set)]
+    additional_data: Option<PyAdditionalData>,
+    #[pyo3(get, set)]
+    language_template: Option<PyLanguageTemplate>,
+    #[pyo3(get, set)]
+    transaction_context: Option<String>,
+    #[pyo3(get, set)]
+    end_to_end_id: Option<String>,
+    #[pyo3(get, set)]
+    validity: Option<PyValidity>,
+    /// `"static"` or `"dynamic"` (or the tag 01 value), inferred from the
+    /// amount when `None`
+    #[pyo3(get, set)]
+    point_of_initiation: Option<String>,
+}
+
+impl PyQRBuilder {
+    fn to_builder(&self) -> PyResult<QRBuilder> {
+        let mut builder = QRBuilder::new()
+            .merchant_name(&self.merchant_name)
+            .merchant_city(&self.merchant_city)
+            .merchant_category_code(&self.merchant_category_code);
+
+        for scheme in &self.schemes {
+            builder = builder.add_scheme(scheme.0.clone());
+        }
+        if let Some(ref amount) = self.transaction_amount {
+            builder = builder.transaction_amount(amount);
+        }
+        if let Some(ref data) = self.additional_data {
+            builder = builder.additional_data(data.clone().into());
+        }
+        if let Some(ref template) = self.language_template {
+            builder = builder.language_template(template.clone().into());
+        }
+        if let Some(ref context) = self.transaction_context {
+            builder = builder.transaction_context(context);
+        }
+        if let Some(ref id) = self.end_to_end_id {
+            builder = builder.end_to_end_id(id);
+        }
+        if let Some(ref validity) = self.validity {
+            builder = builder.validity(validity.clone().into());
+        }
+        if let Some(ref point_of_initiation) = self.point_of_initiation {
+            let method = match point_of_initiation.as_str() {
+                "static" => Some(PointOfInitiation::Static),
+                "dynamic" => Some(PointOfInitiation::Dynamic),
+                code => PointOfInitiation::from_code(code),
+            }
+            .ok_or_else(|| {
+                to_py_err(CoreError::InvalidValue {
+                    field: "point_of_initiation".to_string(),
+                    value: point_of_initiation.clone(),
+                })
+            })?;
+            builder = builder.point_of_initiation(method);
+        }
+
+        Ok(builder)
+    }
+}
+
+#[pymethods]
+impl PyQRBuilder {
+    #[new]
+    #[pyo3(signature = (
+        merchant_name = String::new(),
+        merchant_city = String::new(),
+        merchant_category_code = String::new(),
+        schemes = Vec::new(),
+        transaction_amount = None,
+        additional_data = None,
+        language_template = None,
+        transaction_context = None,
+        end_to_end_id = None,
+        validity = None,
+        point_of_initiation = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        merchant_name: String,
+        merchant_city: String,
+        merchant_category_code: String,
+        schemes: Vec<PySchemeConfig>,
+        transaction_amount: Option<String>,
+        additional_data: Option<PyAdditionalData>,
+        language_template: Option<PyLanguageTemplate>,
+        transaction_context: Option<String>,
+        end_to_end_id: Option<String>,
+        validity: Option<PyValidity>,
+        point_of_initiation: Option<String>,
+    ) -> Self {
+        Self {
+            merchant_name,
+            merchant_city,
+            merchant_category_code,
+            schemes,
+            transaction_amount,
+            additional_data,
+            language_template,
+            transaction_context,
+            end_to_end_id,
+            validity,
+            point_of_initiation,
+        }
+    }
+
+    /// Add a payment scheme
+    fn add_scheme(&mut self, scheme: PySchemeConfig) {
+        self.schemes.push(scheme);
+    }
+
+    /// Build the payload string
+    fn build_payload(&self) -> PyResult<String> {
+        self.to_builder()?.build_payload().map_err(to_py_err)
+    }
+
+    /// Build the QR code as SVG markup
+    #[pyo3(signature = (size = 256))]
+    fn build_svg(&self, size: u32) -> PyResult<String> {
+        self.to_builder()?.build_svg(size).map_err(to_py_err)
+    }
+}
+
+/// Decoded QR payload
+#[pyclass(name = "ParsedQR", module = "ethqr_gen", frozen)]
+struct PyParsedQR {
+    #[pyo3(get)]
+    payload_format_indicator: String,
+    #[pyo3(get)]
+    point_of_initiation: Option<String>,
+    #[pyo3(get)]
+    schemes: Vec<PySchemeConfig>,
+    #[pyo3(get)]
+    merchant_category_code: String,
+    #[pyo3(get)]
+    transaction_currency: String,
+    #[pyo3(get)]
+    transaction_amount: Option<String>,
+    #[pyo3(get)]
+    country_code: String,
+    #[pyo3(get)]
+    merchant_name: String,
+    #[pyo3(get)]
+    merchant_city: String,
+    #[pyo3(get)]
+    additional_data: Option<PyAdditionalData>,
+    #[pyo3(get)]
+    language_template: Option<PyLanguageTemplate>,
+    #[pyo3(get)]
+    transaction_context: Option<String>,
+    #[pyo3(get)]
+    end_to_end_id: Option<String>,
+    #[pyo3(get)]
+    validity: Option<PyValidity>,
+    #[pyo3(get)]
+    crc: String,
+    /// `(id, value)` pairs for tags not mapped onto any attribute
+    #[pyo3(get)]
+    unknown_tags: Vec<(String, String)>,
+    #[pyo3(get)]
+    is_dynamic: bool,
+}
+
+/// Parse and verify a complete QR payload
+#[pyfunction]
+fn parse(payload: &str) -> PyResult<PyParsedQR> {
+    let parsed = parser::parse(payload).map_err(to_py_err)?;
+
+    Ok(PyParsedQR {
+        is_dynamic: parsed.is_dynamic(),
+        payload_format_indicator: parsed.payload_format_indicator,
+        point_of_initiation: parsed.point_of_initiation,
+        schemes: parsed.schemes.into_iter().map(PySchemeConfig).collect(),
+        merchant_category_code: parsed.merchant_category_code,
+        transaction_currency: parsed.transaction_currency,
+        transaction_amount: parsed.transaction_amount,
+        country_code: parsed.country_code,
+        merchant_name: parsed.merchant_name,
+        merchant_city: parsed.merchant_city,
+        additional_data: parsed.additional_data.map(Into::into),
+        language_template: parsed.language_template.map(Into::into),
+        transaction_context: parsed.transaction_context,
+        end_to_end_id: parsed.end_to_end_id,
+        validity: parsed.validity.map(Into::into),
+        crc: parsed.crc,
+        unknown_tags: parsed
+            .unknown_tags
+            .into_iter()
+            .map(|tag| (tag.id, tag.value))
+            .collect(),
+    })
+}
+
+/// Check the CRC at the end of a payload
+#[pyfunction]
+fn verify_crc(payload: &str) -> bool {
+    crc::verify_crc(payload)
+}
+
+/// CRC-16/CCITT-FALSE of `data` as four uppercase hex digits
+#[pyfunction]
+fn calculate_crc(data: &str) -> String {
+    crc::calculate_crc16(data)
+}
+
+#[pymodule]
+mod _ethqr {
+    #[pymodule_export]
+    use super::{
+        BuilderError, ExpiredError, ImageError, InsufficientContrastError, InvalidCRCError,
+        InvalidFormatError, InvalidValueError, IoError, MissingFieldError, NotYetValidError,
+        PayloadTooLongError, QRCodeError, QRError, UnsupportedSchemeError, ValidationError,
+        ValueTooLongError,
+    };
+    #[pymodule_export]
+    use super::{
+        PyAdditionalData, PyLanguageTemplate, PyParsedQR, PyQRBuilder, PySchemeConfig, PyValidity,
+        calculate_crc, parse, verify_crc,
+    };
+}
@@ -0,0 +1,132 @@
+import pytest
+
+import ethqr_gen
+from ethqr_gen import AdditionalData, LanguageTemplate, QRBuilder, SchemeConfig, Validity
+
+
+def builder():
+    return QRBuilder(
+        merchant_name="Restaurant",
+        merchant_city="Dire Dawa",
+        merchant_category_code="5812",
+        schemes=[
+            SchemeConfig.ips_et(
+                "581b314e257f41bfbbdc6384daa31d16", "CBETETAA", "10000171234567890"
+            )
+        ],
+        transaction_amount="50.00",
+        additional_data=AdditionalData(bill_number="INV-001", reference_label="ORDER-123"),
+    )
+
+
+def test_round_trip():
+    payload = builder().build_payload()
+    parsed = ethqr_gen.parse(payload)
+
+    assert parsed.is_dynamic
+    assert parsed.merchant_name == "Restaurant"
+    assert parsed.transaction_amount == "50.00"
+    assert parsed.schemes[0].scheme == "ips_et"
+    assert parsed.schemes[0].bic == "CBETETAA"
+    assert parsed.additional_data == AdditionalData(
+        bill_number="INV-001", reference_label="ORDER-123"
+    )
+    assert parsed.crc == payload[-4:]
+
+
+def test_crc():
+    payload = builder().build_payload()
+
+    assert ethqr_gen.verify_crc(payload)
+    assert ethqr_gen.calculate_crc(payload[:-4]) == payload[-4:]
+    assert not ethqr_gen.verify_crc(payload[:-4] + "0000")
+
+
+def test_add_scheme_and_attributes():
+    qr = QRBuilder(merchant_name="Shop", merchant_city="Adama", merchant_category_code="5411")
+    qr.add_scheme(SchemeConfig.visa("4111111111111111"))
+    qr.transaction_context = "POS"
+
+    assert qr.schemes == [SchemeConfig.visa("4111111111111111")]
+    assert repr(qr.schemes[0]) == 'SchemeConfig.visa("4111111111111111")'
+    assert ethqr_gen.parse(qr.build_payload()).transaction_context == "POS"
+    assert qr.build_svg(128).startswith("<?xml")
+
+
+def test_language_template():
+    qr = builder()
+    qr.language_template = LanguageTemplate("am", "ምግብ ቤት", merchant_city="ድሬ ዳዋ")
+    parsed = ethqr_gen.parse(qr.build_payload())
+
+    assert parsed.language_template == LanguageTemplate("am", "ምግብ ቤት", "ድሬ ዳዋ")
+    assert parsed.language_template.merchant_city == "ድሬ ዳዋ"
+    assert ethqr_gen.parse(builder().build_payload()).language_template is None
+
+
+def test_end_to_end_id():
+    qr = builder()
+    qr.end_to_end_id = "E2E-20251009-0001"
+
+    assert ethqr_gen.parse(qr.build_payload()).end_to_end_id == "E2E-20251009-0001"
+    assert ethqr_gen.parse(builder().build_payload()).end_to_end_id is None
+
+
+def test_validity():
+    now = 1_760_000_000
+    qr = builder()
+    qr.validity = Validity.for_duration(now, 600)
+    parsed = ethqr_gen.parse(qr.build_payload())
+
+    assert parsed.validity == Validity(now + 600, not_before=now)
+    parsed.validity.check(now + 599)
+    with pytest.raises(ethqr_gen.ExpiredError):
+        parsed.validity.check(now + 600)
+    with pytest.raises(ethqr_gen.NotYetValidError):
+        parsed.validity.check(now - 1)
+
+    # Static codes cannot expire
+    qr.transaction_amount = None
+    with pytest.raises(ethqr_gen.ValidationError):
+        qr.build_payload()
+
+
+def test_point_of_initiation():
+    # Dynamic request-to-pay identified by an end-to-end ID instead of an amount
+    qr = builder()
+    qr.transaction_amount = None
+    qr.point_of_initiation = "dynamic"
+    with pytest.raises(ethqr_gen.ValidationError):
+        qr.build_payload()
+
+    qr.end_to_end_id = "RTP-1"
+    parsed = ethqr_gen.parse(qr.build_payload())
+    assert parsed.is_dynamic
+    assert parsed.transaction_amount is None
+    assert parsed.end_to_end_id == "RTP-1"
+
+    # Static code with a fixed amount
+    qr = builder()
+    qr.point_of_initiation = "11"
+    parsed = ethqr_gen.parse(qr.build_payload())
+    assert not parsed.is_dynamic
+    assert parsed.point_of_initiation == "11"
+    assert parsed.transaction_amount == "50.00"
+
+    qr.point_of_initiation = "sometimes"
+    with pytest.raises(ethqr_gen.InvalidValueError):
+        qr.build_payload()
+
+
+def test_errors_map_to_exceptions():
+    qr = builder()
+    qr.merchant_name = "A merchant name that is far too long"
+    with pytest.raises(ethqr_gen.ValueTooLongError):
+        qr.build_payload()
+
+    payload = builder().build_payload()
+    with pytest.raises(ethqr_gen.InvalidCRCError):
+        ethqr_gen.parse(payload[:-4] + "0000")
+
+    with pytest.raises(ethqr_gen.QRError):
+        ethqr_gen.parse("00")
+    assert issubclass(ethqr_gen.QRError, ValueError)
@@ -0,0 +1,478 @@
+//! Batch generation from CSV
+//!
+//! Reads one merchant per CSV row, builds a payload (and optionally an image)
+//! for each, and writes a results CSV. Rows that fail are reported in the
+//! results and do not stop the rest of the batch.
+//!
+//! Input columns are matched by header name, case-insensitively:
+//!
+//! | Column | Builder field |
+//! | --- | --- |
+//! | `id` | Row identifier, used for image file names |
+//! | `name`, `city`, `mcc` | Merchant name, city and category code |
+//! | `amount`, `context` | Transaction amount and context |
+//! | `visa`, `mastercard`, `unionpay` | Card scheme account information |
+//! | `ips_guid`, `ips_bic`, `ips_account` | [`SchemeConfig::ips_et`] parameters |
+//! | `bill_number`, `reference_label`, ... | [`AdditionalData`] fields by name |
+//!
+//! Empty cells are treated as absent. The results CSV has the columns `row`,
+//! `id`, `payload`, `crc` and `errors`, where `errors` joins all problems found
+//! for the row with `"; "`.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::batch::BatchProcessor;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let input = "\
+//! id,name,city,mcc,ips_guid,ips_bic,ips_account
+//! m1,Coffee Shop,Addis Ababa,5812,581b314e257f41bfbbdc6384daa31d16,CBETETAA,10000171234567890
+//! m2,Bad Merchant,Adama,58,581b314e257f41bfbbdc6384daa31d16,CBETETAA,10000171234567890
+//! ";
+//!
+//! let mut output = Vec::new();
+//! let summary = BatchProcessor::new().process(input.as_bytes(), &mut output)?;
+//! assert_eq!((summary.succeeded, summary.failed), (1, 1));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::QRBuilder;
+use crate::error::{QRError, Result};
+use crate::export::{ImageEncoder, ImageFormat};
+use crate::fields::{AdditionalData, SchemeConfig};
+use crate::parser;
+use crate::validation;
+
+/// Recognised input columns
+const COLUMNS: [&str; 25] = [
+    "id",
+    "name",
+    "city",
+    "mcc",
+    "amount",
+    "context",
+    "visa",
+    "mastercard",
+    "unionpay",
+    "ips_guid",
+    "ips_bic",
+    "ips_account",
+    "bill_number",
+    "mobile_number",
+    "store_label",
+    "loyalty_number",
+    "reference_label",
+    "customer_label",
+    "terminal_number",
+    "purpose",
+    "additional_customer_data",
+    "merchant_tax_id",
+    "merchant_channel",
+    "due_date",
+    "amount_after_due_date",
+];
+
+/// Outcome of a single input row
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchRecord {
+    /// One-based data row number, excluding the header
+    pub row: usize,
+    pub id: Option<String>,
+    pub payload: Option<String>,
+    pub crc: Option<String>,
+    pub errors: Vec<String>,
+}
+
+impl BatchRecord {
+    /// Whether the row produced a payload without errors
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.payload.is_some() && self.errors.is_empty()
+    }
+}
+
+/// Totals for a processed batch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// Processor turning merchant CSV rows into payloads and images
+#[derive(Debug, Clone, Default)]
+pub struct BatchProcessor {
+    image_dir: Option<PathBuf>,
+    image_encoder: ImageEncoder,
+}
+
+impl BatchProcessor {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write an image for every successful row into `dir`
+    ///
+    /// Files are named after the `id` column, falling back to the row number.
+    /// A row whose file name was already written by an earlier row gets an
+    /// error instead of overwriting that image.
+    pub fn image_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.image_dir = Some(dir.into());
+        self
+    }
+
+    /// Set the encoder used for row images (PNG by default)
+    pub fn image_encoder(mut self, encoder: ImageEncoder) -> Self {
+        self.image_encoder = encoder;
+        self
+    }
+
+    /// Process every row of `input` and write the results CSV to `output`
+    ///
+    /// # Errors
+    /// Returns an error if the header row is missing or contains unknown
+    /// columns, or if reading input or writing output fails. Problems with
+    /// individual rows are recorded in the results instead.
+    pub fn process<R: Read, W: Write>(&self, input: R, output: W) -> Result<BatchSummary> {
+        let mut writer = csv::Writer::from_writer(output);
+        writer.write_record(["row", "id", "payload", "crc", "errors"])?;
+
+        let mut summary = BatchSummary::default();
+        self.process_with(input, |record| {
+            summary.total += 1;
+            if record.is_ok() {
+                summary.succeeded += 1;
+            } else {
+                summary.failed += 1;
+            }
+
+            writer.write_record([
+                record.row.to_string().as_str(),
+                record.id.as_deref().unwrap_or_default(),
+                record.payload.as_deref().unwrap_or_default(),
+                record.crc.as_deref().unwrap_or_default(),
+                record.errors.join("; ").as_str(),
+            ])?;
+            Ok(())
+        })?;
+
+        writer.flush()?;
+
+        Ok(summary)
+    }
+
+    /// Process every row of `input`, passing each result to `handler`
+    ///
+    /// # Errors
+    /// Returns an error if the header row is invalid, reading fails, or the
+    /// handler returns an error.
+    pub fn process_with<R, F>(&self, input: R, mut handler: F) -> Result<()>
+    where
+        R: Read,
+        F: FnMut(BatchRecord) -> Result<()>,
+    {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(input);
+
+        let headers: Vec<String> = reader
+            .headers()?
+            .iter()
+            .map(str::to_ascii_lowercase)
+            .collect();
+        if let Some(unknown) = headers.iter().find(|h| !COLUMNS.contains(&h.as_str())) {
+            return Err(QRError::InvalidFormat {
+                message: format!("unknown CSV column '{unknown}'"),
+            });
+        }
+
+        // Lowercased, since file systems may ignore case
+        let mut image_names = HashSet::new();
+        for (index, result) in reader.records().enumerate() {
+            let mut record = BatchRecord {
+                row: index + 1,
+                ..BatchRecord::default()
+            };
+
+            match result {
+                Ok(row) => {
+                    let cell = |column: &str| {
+                        headers
+                            .iter()
+                            .position(|h| h == column)
+                            .and_then(|i| row.get(i))
+                            .filter(|value| !value.is_empty())
+                    };
+                    record.id = cell("id").map(str::to_string);
+                    self.process_row(&cell, &mut record, &mut image_names);
+                }
+                Err(e) => record.errors.push(e.to_string()),
+            }
+
+            handler(record)?;
+        }
+
+        Ok(())
+    }
+
+    fn process_row<'a>(
+        &self,
+        cell: &dyn Fn(&str) -> Option<&'a str>,
+        record: &mut BatchRecord,
+        image_names: &mut HashSet<String>,
+    ) {
+        let builder = match builder_from_row(cell) {
+            Ok(builder) => builder,
+            Err(e) => {
+                record.errors.push(e.to_string());
+                return;
+            }
+        };
+
+        let payload = match builder.build_payload() {
+            Ok(payload) => payload,
+            Err(e) => {
+                record.errors.push(e.to_string());
+                return;
+            }
+        };
+
+        record.errors.extend(
+            validation::validate(&payload)
+                .errors()
+                .map(ToString::to_string),
+        );
+        record.crc = parser::parse(&payload).ok().map(|parsed| parsed.crc);
+
+        if let Some(ref dir) = self.image_dir
+            && let Err(e) = self.write_image(&builder, dir, record, image_names)
+        {
+            record.errors.push(e.to_string());
+        }
+
+        record.payload = Some(payload);
+    }
+
+    fn write_image(
+        &self,
+        builder: &QRBuilder,
+        dir: &Path,
+        record: &BatchRecord,
+        image_names: &mut HashSet<String>,
+    ) -> Result<()> {
+        let stem = record
+            .id
+            .as_deref()
+            .filter(|id| {
+                id.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            })
+            .map_or_else(|| format!("row-{}", record.row), str::to_string);
+
+        let extension = match self.image_encoder.format() {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg { .. } => "jpg",
+            ImageFormat::WebP => "webp",
+        };
+
+        let file_name = format!("{stem}.{extension}");
+        if !image_names.insert(file_name.to_ascii_lowercase()) {
+            return Err(QRError::InvalidFormat {
+                message: format!("image {file_name} was already written for an earlier row"),
+            });
+        }
+
+        let bytes = builder.build_image_bytes(&self.image_encoder)?;
+        fs::write(dir.join(file_name), bytes)?;
+
+        Ok(())
+    }
+}
+
+fn builder_from_row<'a>(cell: &dyn Fn(&str) -> Option<&'a str>) -> Result<QRBuilder> {
+    let mut builder = QRBuilder::new()
+        .merchant_name(cell("name").unwrap_or_default())
+        .merchant_city(cell("city").unwrap_or_default())
+        .merchant_category_code(cell("mcc").unwrap_or_default());
+
+    if let Some(account) = cell("visa") {
+        builder = builder.add_scheme(SchemeConfig::visa(account));
+    }
+    if let Some(account) = cell("mastercard") {
+        builder = builder.add_scheme(SchemeConfig::mastercard(account));
+    }
+    if let Some(account) = cell("unionpay") {
+        builder = builder.add_scheme(SchemeConfig::UnionPay {
+            account_info: account.to_string(),
+        });
+    }
+
+    match (cell("ips_guid"), cell("ips_bic"), cell("ips_account")) {
+        (Some(guid), Some(bic), Some(account)) => {
+            builder = builder.add_scheme(SchemeConfig::ips_et(guid, bic, account));
+        }
+        (None, None, None) => {}
+        _ => {
+            return Err(QRError::MissingField {
+                field: "ips_guid, ips_bic and ips_account".to_string(),
+            });
+        }
+    }
+
+    if let Some(amount) = cell("amount") {
+        builder = builder.transaction_amount(amount);
+    }
+    if let Some(context) = cell("context") {
+        builder = builder.transaction_context(context);
+    }
+
+    let owned = |column: &str| cell(column).map(str::to_string);
+    let additional_data = AdditionalData {
+        bill_number: owned("bill_number"),
+        mobile_number: owned("mobile_number"),
+        store_label: owned("store_label"),
+        loyalty_number: owned("loyalty_number"),
+        reference_label: owned("reference_label"),
+        customer_label: owned("customer_label"),
+        terminal_number: owned("terminal_number"),
+        purpose: owned("purpose"),
+        additional_customer_data: owned("additional_customer_data"),
+        merchant_tax_id: owned("merchant_tax_id"),
+        merchant_channel: owned("merchant_channel"),
+        due_date: owned("due_date"),
+        amount_after_due_date: owned("amount_after_due_date"),
+    };
+    if additional_data != AdditionalData::default() {
+        builder = builder.additional_data(additional_data);
+    }
+
+    Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+ID,Name,City,MCC,Amount,IPS_GUID,IPS_BIC,IPS_Account,Reference_Label
+m1,Coffee Shop,Addis Ababa,5812,,581b314e257f41bfbbdc6384daa31d16,CBETETAA,10000171234567890,
+m2,Taxi,Adama,4121,85.00,581b314e257f41bfbbdc6384daa31d16,CBETETAA,10000171234567890,RIDE-1
+m3,A Merchant Name That Is Far Too Long,Adama,5812,,581b314e257f41bfbbdc6384daa31d16,CBETETAA,1
+m4,Shop,Adama,5812,,581b314e257f41bfbbdc6384daa31d16,,1
+m5,Kiosk,Hawassa,5411,abc,,,,
+";
+
+    fn records() -> Vec<BatchRecord> {
+        let mut records = Vec::new();
+        BatchProcessor::new()
+            .process_with(INPUT.as_bytes(), |record| {
+                records.push(record);
+                Ok(())
+            })
+            .unwrap();
+        records
+    }
+
+    #[test]
+    fn test_continues_past_bad_rows() {
+        let records = records();
+        assert_eq!(records.len(), 5);
+
+        assert!(records[0].is_ok());
+        assert!(records[1].is_ok());
+        let parsed = parser::parse(records[1].payload.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            parsed.additional_data.unwrap().reference_label.as_deref(),
+            Some("RIDE-1")
+        );
+        assert_eq!(records[1].crc, Some(parsed.crc));
+
+        assert!(records[2].errors[0].contains("name"));
+        assert!(records[3].errors[0].contains("ips_bic"));
+        // No scheme and an invalid amount
+        assert!(records[4].payload.is_none());
+        assert_eq!(records[4].id.as_deref(), Some("m5"));
+    }
+
+    #[test]
+    fn test_results_csv() {
+        let mut output = Vec::new();
+        let summary = BatchProcessor::new()
+            .process(INPUT.as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(
+            summary,
+            BatchSummary {
+                total: 5,
+                succeeded: 2,
+                failed: 3,
+            }
+        );
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "row,id,payload,crc,errors");
+        assert!(lines[1].starts_with("1,m1,000201010211"));
+        assert_eq!(lines.len(), 6);
+    }
+
+    #[test]
+    fn test_validation_errors_are_reported() {
+        let input = "name,city,mcc,amount,visa\nShop,Adama,5812,abc,4111111111111111\n";
+        let mut records = Vec::new();
+        BatchProcessor::new()
+            .process_with(input.as_bytes(), |record| {
+                records.push(record);
+                Ok(())
+            })
+            .unwrap();
+
+        assert!(records[0].payload.is_some());
+        assert!(records[0].errors[0].contains("transaction amount"));
+    }
+
+    #[test]
+    fn test_duplicate_image_names_are_reported() {
+        let dir = std::env::temp_dir().join(format!("ethqr-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // "M1" matches "m1" on case-insensitive file systems, and the
+        // unusable id "m 2" falls back to "row-4", the id of row 3
+        let input = "\
+id,name,city,mcc,visa
+m1,Shop,Adama,5812,4111111111111111
+M1,Shop,Adama,5812,4111111111111111
+row-4,Shop,Adama,5812,4111111111111111
+m 2,Shop,Adama,5812,4111111111111111
+";
+        let mut records = Vec::new();
+        BatchProcessor::new()
+            .image_dir(&dir)
+            .process_with(input.as_bytes(), |record| {
+                records.push(record);
+                Ok(())
+            })
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let errors: Vec<usize> = records.iter().map(|r| r.errors.len()).collect();
+        assert_eq!(errors, [0, 1, 0, 1]);
+        assert!(records[1].errors[0].contains("M1.png"));
+        assert!(records[3].payload.is_some());
+    }
+
+    #[test]
+    fn test_unknown_column() {
+        let result = BatchProcessor::new().process("name,cty\n".as_bytes(), Vec::new());
+        assert!(matches!(result, Err(QRError::InvalidFormat { .. })));
+    }
+}
@@ -0,0 +1,51 @@
+//! `ethqr-server` local HTTP service
+//!
+//! Serves the JSON endpoints described in [`ethqr_gen::server`].
+//!
+//! Run with: cargo run --features server --bin ethqr-server -- --addr 127.0.0.1:8080
+
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use ethqr_gen::server::{DEFAULT_MAX_BODY_SIZE, DEFAULT_MAX_IMAGE_SIZE, QRServer};
+
+#[derive(Parser)]
+#[command(
+    name = "ethqr-server",
+    version,
+    about = "HTTP service for generating, decoding and validating Ethiopian QR payment codes"
+)]
+struct Cli {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    addr: String,
+    /// Largest request body accepted, in bytes
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_BODY_SIZE)]
+    max_body_size: usize,
+    /// Largest image size a request may ask for, in pixels
+    #[arg(long, value_name = "PIXELS", default_value_t = DEFAULT_MAX_IMAGE_SIZE)]
+    max_image_size: u32,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let server = QRServer::new()
+        .max_body_size(cli.max_body_size)
+        .max_image_size(cli.max_image_size);
+
+    let listener = match server.bind(&cli.addr) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match listener.local_addr() {
+        Some(addr) => eprintln!("listening on http://{addr}"),
+        None => eprintln!("listening on http://{}", cli.addr),
+    }
+    listener.run();
+    ExitCode::SUCCESS
+}
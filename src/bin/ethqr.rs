@@ -0,0 +1,643 @@
+//! `ethqr` command-line tool
+//!
+//! Generates, decodes, validates, repairs and renders Ethiopian QR payment codes.
+//!
+//! Run with: cargo run --features cli --bin ethqr -- --help
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde_json::{Value, json};
+
+use ethqr_gen::batch::{BatchProcessor, BatchSummary};
+use ethqr_gen::error::QRError;
+use ethqr_gen::export::{ImageEncoder, ImageFormat};
+use ethqr_gen::fields::{
+    AdditionalData, LanguageTemplate, PointOfInitiation, SchemeConfig, Validity,
+};
+use ethqr_gen::parser::{self, ParsedQR};
+use ethqr_gen::profile::MerchantProfile;
+use ethqr_gen::repair::{self, CrcDiagnosis};
+use ethqr_gen::terminal::{TerminalRenderer, TerminalStyle};
+use ethqr_gen::validation::{self, ValidationReport};
+use ethqr_gen::{QRBuilder, constants};
+use image::{DynamicImage, Luma};
+use qrcode::QrCode;
+use qrcode::render::svg;
+
+#[derive(Parser)]
+#[command(
+    name = "ethqr",
+    version,
+    about = "Generate, decode and validate Ethiopian QR payment codes"
+)]
+struct Cli {
+    /// Print machine-readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build a payload from merchant and transaction details
+    Generate(Box<GenerateArgs>),
+    /// Decode a payload into its fields
+    Decode {
+        /// Payload string, or `-` to read from stdin
+        payload: Option<String>,
+        /// Read the payload from a QR code in this image file instead
+        #[arg(long, value_name = "FILE", conflicts_with = "payload")]
+        image: Option<PathBuf>,
+    },
+    /// Check a payload against the standard and report every issue
+    #[command(alias = "lint")]
+    Validate {
+        /// Payload string, or `-` to read from stdin
+        payload: Option<String>,
+    },
+    /// Diagnose a payload failing its CRC and suggest likely repairs
+    Repair {
+        /// Payload string, or `-` to read from stdin
+        payload: Option<String>,
+    },
+    /// Render a payload as an image or in the terminal
+    Render(RenderArgs),
+    /// Generate payloads for every merchant row of a CSV file
+    Batch(BatchArgs),
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// Merchant profile file (TOML, YAML or JSON) supplying the static fields
+    #[arg(long, value_name = "FILE")]
+    profile: Option<String>,
+    /// Merchant name (tag 59)
+    #[arg(long, required_unless_present = "profile")]
+    name: Option<String>,
+    /// Merchant city (tag 60)
+    #[arg(long, required_unless_present = "profile")]
+    city: Option<String>,
+    /// Merchant category code (tag 52)
+    #[arg(long, required_unless_present = "profile")]
+    mcc: Option<String>,
+    /// Transaction amount, makes the QR code dynamic unless --static (tag 54)
+    #[arg(long)]
+    amount: Option<String>,
+    /// Issue a static QR code even with an amount (tag 01)
+    #[arg(long = "static", conflicts_with = "dynamic")]
+    static_qr: bool,
+    /// Issue a dynamic QR code even without an amount (tag 01)
+    #[arg(long)]
+    dynamic: bool,
+    /// Transaction context (tag 80)
+    #[arg(long)]
+    context: Option<String>,
+    /// Alternate language as an ISO 639 code (tag 64-00)
+    #[arg(long, requires = "alt_name")]
+    language: Option<String>,
+    /// Merchant name in the alternate language (tag 64-01)
+    #[arg(long, requires = "language")]
+    alt_name: Option<String>,
+    /// Merchant city in the alternate language (tag 64-02)
+    #[arg(long, requires = "language")]
+    alt_city: Option<String>,
+    /// End-to-end ID for request-to-pay (tag 84)
+    #[arg(long)]
+    end_to_end_id: Option<String>,
+    /// Reject payment after this many seconds from now (tag 90)
+    #[arg(long, value_name = "SECONDS")]
+    valid_for: Option<u64>,
+    /// Visa merchant account
+    #[arg(long, value_name = "ACCOUNT")]
+    visa: Vec<String>,
+    /// Mastercard merchant account
+    #[arg(long, value_name = "ACCOUNT")]
+    mastercard: Vec<String>,
+    /// UnionPay merchant account
+    #[arg(long, value_name = "ACCOUNT")]
+    unionpay: Vec<String>,
+    /// IPS ET merchant account
+    #[arg(long, value_name = "GUID,BIC,ACCOUNT", value_parser = parse_ips_et)]
+    ips_et: Vec<SchemeConfig>,
+    #[command(flatten)]
+    additional_data: AdditionalDataArgs,
+}
+
+#[derive(Args)]
+#[group(multiple = true)]
+struct AdditionalDataArgs {
+    /// Bill or invoice number (tag 62-01)
+    #[arg(long)]
+    bill_number: Option<String>,
+    /// Merchant mobile number (tag 62-02)
+    #[arg(long)]
+    mobile_number: Option<String>,
+    /// Store label (tag 62-03)
+    #[arg(long)]
+    store_label: Option<String>,
+    /// Loyalty number (tag 62-04)
+    #[arg(long)]
+    loyalty_number: Option<String>,
+    /// Reference label (tag 62-05)
+    #[arg(long)]
+    reference_label: Option<String>,
+    /// Customer label (tag 62-06)
+    #[arg(long)]
+    customer_label: Option<String>,
+    /// Terminal number (tag 62-07)
+    #[arg(long)]
+    terminal_number: Option<String>,
+    /// Purpose of transaction (tag 62-08)
+    #[arg(long)]
+    purpose: Option<String>,
+    /// Additional customer data request (tag 62-09)
+    #[arg(long)]
+    additional_customer_data: Option<String>,
+    /// Merchant tax ID (tag 62-10)
+    #[arg(long)]
+    merchant_tax_id: Option<String>,
+    /// Merchant channel (tag 62-11)
+    #[arg(long)]
+    merchant_channel: Option<String>,
+    /// Due date in DDMMYYYY format (tag 62-50)
+    #[arg(long)]
+    due_date: Option<String>,
+    /// Amount after due date (tag 62-51)
+    #[arg(long)]
+    amount_after_due_date: Option<String>,
+}
+
+#[derive(Args)]
+struct RenderArgs {
+    /// Payload string, or `-` to read from stdin
+    payload: Option<String>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = RenderFormat::Terminal)]
+    format: RenderFormat,
+    /// Output file, or `-` for stdout
+    #[arg(long, short, default_value = "-")]
+    output: String,
+    /// Minimum image size in pixels (PNG and SVG)
+    #[arg(long, default_value_t = 256)]
+    size: u32,
+    /// Swap dark and light modules for dark terminal themes
+    #[arg(long)]
+    invert: bool,
+}
+
+#[derive(Args)]
+struct BatchArgs {
+    /// Input CSV file, or `-` to read from stdin
+    input: String,
+    /// Results CSV file, or `-` for stdout
+    #[arg(long, short, default_value = "-")]
+    output: String,
+    /// Directory to write one PNG image per successful row into
+    #[arg(long, value_name = "DIR")]
+    images: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RenderFormat {
+    Png,
+    Svg,
+    Terminal,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(&cli) {
+        Ok(code) => code,
+        Err(e) => {
+            if cli.json {
+                println!("{}", json!({ "error": e.to_string() }));
+            } else {
+                eprintln!("error: {e}");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    match cli.command {
+        Command::Generate(ref args) => {
+            let payload = builder_from_args(args)?.build_payload()?;
+            if cli.json {
+                let parsed = parser::parse(&payload)?;
+                println!(
+                    "{}",
+                    json!({
+                        "payload": payload,
+                        "crc": parsed.crc,
+                        "length": payload.len(),
+                        "dynamic": parsed.is_dynamic(),
+                    })
+                );
+            } else {
+                println!("{payload}");
+            }
+        }
+        Command::Decode {
+            ref payload,
+            ref image,
+        } => {
+            let payload = match image {
+                Some(path) => read_image_payload(path)?,
+                None => read_payload(payload.as_deref())?,
+            };
+            let parsed = parser::parse(&payload)?;
+            if cli.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&parsed_to_json(&parsed)?)?
+                );
+            } else {
+                print_parsed(&parsed);
+            }
+        }
+        Command::Validate { ref payload } => {
+            let report = validation::validate(&read_payload(payload.as_deref())?);
+            if cli.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report_to_json(&report))?
+                );
+            } else if report.issues.is_empty() {
+                println!("payload is valid");
+            } else {
+                for issue in &report.issues {
+                    println!("{issue}");
+                }
+            }
+
+            if !report.is_valid() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Repair { ref payload } => {
+            let diagnosis = repair::diagnose_crc(&read_payload(payload.as_deref())?);
+            if cli.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&diagnosis_to_json(&diagnosis)?)?
+                );
+            } else {
+                print_diagnosis(&diagnosis);
+            }
+
+            if diagnosis.error.is_some() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Render(ref args) => render(args)?,
+        Command::Batch(ref args) => {
+            let summary = batch(args)?;
+            let message = if cli.json {
+                json!({
+                    "total": summary.total,
+                    "succeeded": summary.succeeded,
+                    "failed": summary.failed,
+                })
+                .to_string()
+            } else {
+                format!(
+                    "processed {} rows: {} succeeded, {} failed",
+                    summary.total, summary.succeeded, summary.failed
+                )
+            };
+
+            // Keep stdout clean when it carries the results CSV
+            if args.output == "-" {
+                eprintln!("{message}");
+            } else {
+                println!("{message}");
+            }
+
+            if summary.failed > 0 {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn builder_from_args(args: &GenerateArgs) -> Result<QRBuilder, QRError> {
+    let profile = args
+        .profile
+        .as_deref()
+        .map(MerchantProfile::load)
+        .transpose()?;
+    let mut builder = profile
+        .as_ref()
+        .map_or_else(QRBuilder::new, MerchantProfile::to_builder);
+
+    if let Some(ref name) = args.name {
+        builder = builder.merchant_name(name);
+    }
+    if let Some(ref city) = args.city {
+        builder = builder.merchant_city(city);
+    }
+    if let Some(ref mcc) = args.mcc {
+        builder = builder.merchant_category_code(mcc);
+    }
+
+    for account in &args.visa {
+        builder = builder.add_scheme(SchemeConfig::visa(account));
+    }
+    for account in &args.mastercard {
+        builder = builder.add_scheme(SchemeConfig::mastercard(account));
+    }
+    for account in &args.unionpay {
+        builder = builder.add_scheme(SchemeConfig::UnionPay {
+            account_info: account.clone(),
+        });
+    }
+    for scheme in &args.ips_et {
+        builder = builder.add_scheme(scheme.clone());
+    }
+
+    if let Some(ref amount) = args.amount {
+        builder = builder.transaction_amount(amount);
+    }
+    if args.static_qr {
+        builder = builder.point_of_initiation(PointOfInitiation::Static);
+    } else if args.dynamic {
+        builder = builder.point_of_initiation(PointOfInitiation::Dynamic);
+    }
+    if let Some(ref context) = args.context {
+        builder = builder.transaction_context(context);
+    }
+    // Replaces the profile's language template as a whole
+    if let (Some(language), Some(name)) = (&args.language, &args.alt_name) {
+        let mut template = LanguageTemplate::new(language, name);
+        if let Some(ref city) = args.alt_city {
+            template = template.merchant_city(city);
+        }
+        builder = builder.language_template(template);
+    }
+    if let Some(ref id) = args.end_to_end_id {
+        builder = builder.end_to_end_id(id);
+    }
+    if let Some(seconds) = args.valid_for {
+        builder = builder.validity(Validity::for_duration(unix_now(), seconds));
+    }
+
+    // Command-line values override the profile's additional data defaults
+    let defaults = profile
+        .and_then(|profile| profile.additional_data)
+        .unwrap_or_default();
+    let data = &args.additional_data;
+    let additional_data = defaults.overlay(AdditionalData {
+        bill_number: data.bill_number.clone(),
+        mobile_number: data.mobile_number.clone(),
+        store_label: data.store_label.clone(),
+        loyalty_number: data.loyalty_number.clone(),
+        reference_label: data.reference_label.clone(),
+        customer_label: data.customer_label.clone(),
+        terminal_number: data.terminal_number.clone(),
+        purpose: data.purpose.clone(),
+        additional_customer_data: data.additional_customer_data.clone(),
+        merchant_tax_id: data.merchant_tax_id.clone(),
+        merchant_channel: data.merchant_channel.clone(),
+        due_date: data.due_date.clone(),
+        amount_after_due_date: data.amount_after_due_date.clone(),
+    });
+    if additional_data != AdditionalData::default() {
+        builder = builder.additional_data(additional_data);
+    }
+
+    Ok(builder)
+}
+
+fn parse_ips_et(value: &str) -> Result<SchemeConfig, String> {
+    match value.split(',').collect::<Vec<_>>()[..] {
+        [guid, bic, account] => Ok(SchemeConfig::ips_et(guid, bic, account)),
+        _ => Err("expected GUID,BIC,ACCOUNT".to_string()),
+    }
+}
+
+/// Read the payload from the argument, or stdin when absent or `-`
+fn read_payload(arg: Option<&str>) -> io::Result<String> {
+    match arg {
+        Some(payload) if payload != "-" => Ok(payload.to_string()),
+        _ => {
+            let mut payload = String::new();
+            io::stdin().read_to_string(&mut payload)?;
+            Ok(payload.trim_end_matches(['\r', '\n']).to_string())
+        }
+    }
+}
+
+/// Read the payload from the first QR code found in an image file
+fn read_image_payload(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let image = image::open(path)?.to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare(image);
+    let grids = prepared.detect_grids();
+    let grid = grids
+        .first()
+        .ok_or_else(|| format!("no QR code found in {}", path.display()))?;
+    let (_, payload) = grid.decode()?;
+    Ok(payload)
+}
+
+fn render(args: &RenderArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let payload = read_payload(args.payload.as_deref())?;
+    if payload.len() > constants::MAX_QR_LENGTH {
+        return Err(QRError::PayloadTooLong {
+            length: payload.len(),
+        }
+        .into());
+    }
+    let qr_code = QrCode::new(&payload)?;
+
+    let bytes = match args.format {
+        RenderFormat::Png => {
+            let image = qr_code
+                .render::<Luma<u8>>()
+                .min_dimensions(args.size, args.size)
+                .build();
+            ImageEncoder::new(ImageFormat::Png).encode(&DynamicImage::ImageLuma8(image))?
+        }
+        RenderFormat::Svg => qr_code
+            .render::<svg::Color>()
+            .min_dimensions(args.size, args.size)
+            .build()
+            .into_bytes(),
+        RenderFormat::Terminal => TerminalRenderer::new()
+            .style(TerminalStyle::HalfBlock)
+            .invert(args.invert)
+            .render(&qr_code)
+            .into_bytes(),
+    };
+
+    if args.output == "-" {
+        io::stdout().write_all(&bytes)?;
+    } else {
+        fs::write(&args.output, bytes)?;
+    }
+
+    Ok(())
+}
+
+fn batch(args: &BatchArgs) -> Result<BatchSummary, Box<dyn std::error::Error>> {
+    let mut processor = BatchProcessor::new();
+    if let Some(ref dir) = args.images {
+        fs::create_dir_all(dir)?;
+        processor = processor.image_dir(dir);
+    }
+
+    let input: Box<dyn Read> = if args.input == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(fs::File::open(&args.input)?)
+    };
+    let output: Box<dyn Write> = if args.output == "-" {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(fs::File::create(&args.output)?)
+    };
+
+    Ok(processor.process(input, output)?)
+}
+
+/// Seconds since the Unix epoch by the system clock
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+fn print_parsed(parsed: &ParsedQR) {
+    let kind = if parsed.is_dynamic() {
+        "dynamic"
+    } else {
+        "static"
+    };
+    println!("Type:              {kind}");
+    println!("Merchant name:     {}", parsed.merchant_name);
+    println!("Merchant city:     {}", parsed.merchant_city);
+    println!("Category code:     {}", parsed.merchant_category_code);
+    println!("Currency:          {}", parsed.transaction_currency);
+    println!("Country:           {}", parsed.country_code);
+    if let Some(ref amount) = parsed.transaction_amount {
+        println!("Amount:            {amount}");
+    }
+    if let Some(ref context) = parsed.transaction_context {
+        println!("Context:           {context}");
+    }
+    if let Some(ref id) = parsed.end_to_end_id {
+        println!("End-to-end ID:     {id}");
+    }
+    for scheme in &parsed.schemes {
+        let description = match scheme {
+            SchemeConfig::Visa { account_info } => format!("Visa {account_info}"),
+            SchemeConfig::Mastercard { account_info } => format!("Mastercard {account_info}"),
+            SchemeConfig::UnionPay { account_info } => format!("UnionPay {account_info}"),
+            SchemeConfig::IPSET { guid, bic, account } => {
+                format!("IPS ET {account} at {bic} (GUID {guid})")
+            }
+        };
+        println!("Scheme:            {description}");
+    }
+    if let Some(ref data) = parsed.additional_data {
+        for (name, value) in additional_data_fields(data) {
+            let label = name.replace('_', " ");
+            let (first, rest) = label.split_at(1);
+            println!("{:<19}{value}", format!("{}{rest}:", first.to_uppercase()));
+        }
+    }
+    if let Some(ref validity) = parsed.validity {
+        if let Some(not_before) = validity.not_before {
+            println!("Valid from:        {not_before}");
+        }
+        let status = match validity.check(unix_now()) {
+            Ok(()) => "valid",
+            Err(QRError::NotYetValid { .. }) => "not yet valid",
+            Err(_) => "expired",
+        };
+        println!("Expires:           {} ({status})", validity.expires_at);
+    }
+    if let Some(ref template) = parsed.language_template {
+        println!("Language:          {}", template.language_preference);
+        println!("Alternate name:    {}", template.merchant_name);
+        if let Some(ref city) = template.merchant_city {
+            println!("Alternate city:    {city}");
+        }
+    }
+    for tag in &parsed.unknown_tags {
+        println!("Tag {}:            {}", tag.id, tag.value);
+    }
+    println!("CRC:               {}", parsed.crc);
+}
+
+/// Same shape as the server's `/decode` response, plus whether the code has expired
+fn parsed_to_json(parsed: &ParsedQR) -> Result<Value, serde_json::Error> {
+    let mut value = serde_json::to_value(parsed)?;
+    value["dynamic"] = Value::Bool(parsed.is_dynamic());
+    if let Some(validity) = parsed.validity {
+        value["validity"]["expired"] = Value::Bool(matches!(
+            validity.check(unix_now()),
+            Err(QRError::Expired { .. })
+        ));
+    }
+    Ok(value)
+}
+
+fn additional_data_fields(data: &AdditionalData) -> impl Iterator<Item = (&'static str, &String)> {
+    [
+        ("bill_number", &data.bill_number),
+        ("mobile_number", &data.mobile_number),
+        ("store_label", &data.store_label),
+        ("loyalty_number", &data.loyalty_number),
+        ("reference_label", &data.reference_label),
+        ("customer_label", &data.customer_label),
+        ("terminal_number", &data.terminal_number),
+        ("purpose", &data.purpose),
+        ("additional_customer_data", &data.additional_customer_data),
+        ("merchant_tax_id", &data.merchant_tax_id),
+        ("merchant_channel", &data.merchant_channel),
+        ("due_date", &data.due_date),
+        ("amount_after_due_date", &data.amount_after_due_date),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.as_ref().map(|value| (name, value)))
+}
+
+fn print_diagnosis(diagnosis: &CrcDiagnosis) {
+    match diagnosis.error {
+        None if diagnosis.lowercase_crc => {
+            println!("CRC matches, but its hex digits should be uppercase")
+        }
+        None => println!("CRC is valid"),
+        Some(ref e) => println!("{e}"),
+    }
+    if let Some(ref crc) = diagnosis.expected_crc {
+        println!("expected CRC: {crc}");
+    }
+    if diagnosis.error.is_some() && diagnosis.suggestions.is_empty() {
+        println!("no single-character repair found");
+    }
+    for repair in &diagnosis.suggestions {
+        println!("{}\n  {}", repair.edit, repair.payload);
+    }
+}
+
+fn diagnosis_to_json(diagnosis: &CrcDiagnosis) -> Result<Value, serde_json::Error> {
+    let mut value = serde_json::to_value(diagnosis)?;
+    value["valid"] = Value::Bool(diagnosis.error.is_none());
+    Ok(value)
+}
+
+fn report_to_json(report: &ValidationReport) -> Value {
+    json!({ "valid": report.is_valid(), "issues": report.issues })
+}
@@ -1,28 +1,418 @@
-// CRC-16-CCITT lookup table for fast calculation
-const CRC16_CCITT_TABLE: [u16; 256] = [
-    0x0000, 0x1021, 0x2042, 0x3063, 0x4084, 0x50a5, 0x60c6, 0x70e7, 0x8108, 0x9129, 0xa14a, 0xb16b,
-    0xc18c, 0xd1ad, 0xe1ce, 0xf1ef, 0x1231, 0x0210, 0x3273, 0x2252, 0x52b5, 0x4294, 0x72f7, 0x62d6,
-    0x9339, 0x8318, 0xb37b, 0xa35a, 0xd3bd, 0xc39c, 0xf3ff, 0xe3de, 0x2462, 0x3443, 0x0420, 0x1401,
-    0x64e6, 0x74c7, 0x44a4, 0x5485, 0xa56a, 0xb54b, 0x8528, 0x9509, 0xe5ee, 0xf5cf, 0xc5ac, 0xd58d,
-    0x3653, 0x2672, 0x1611, 0x0630, 0x76d7, 0x66f6, 0x5695, 0x46b4, 0xb75b, 0xa77a, 0x9719, 0x8738,
-    0xf7df, 0xe7fe, 0xd79d, 0xc7bc, 0x48c4, 0x58e5, 0x6886, 0x78a7, 0x0840, 0x1861, 0x2802, 0x3823,
-    0xc9cc, 0xd9ed, 0xe98e, 0xf9af, 0x8948, 0x9969, 0xa90a, 0xb92b, 0x5af5, 0x4ad4, 0x7ab7, 0x6a96,
-    0x1a71, 0x0a50, 0x3a33, 0x2a12, 0xdbfd, 0xcbdc, 0xfbbf, 0xeb9e, 0x9b79, 0x8b58, 0xbb3b, 0xab1a,
-    0x6ca6, 0x7c87, 0x4ce4, 0x5cc5, 0x2c22, 0x3c03, 0x0c60, 0x1c41, 0xedae, 0xfd8f, 0xcdec, 0xddcd,
-    0xad2a, 0xbd0b, 0x8d68, 0x9d49, 0x7e97, 0x6eb6, 0x5ed5, 0x4ef4, 0x3e13, 0x2e32, 0x1e51, 0x0e70,
-    0xff9f, 0xefbe, 0xdfdd, 0xcffc, 0xbf1b, 0xaf3a, 0x9f59, 0x8f78, 0x9188, 0x81a9, 0xb1ca, 0xa1eb,
-    0xd10c, 0xc12d, 0xf14e, 0xe16f, 0x1080, 0x00a1, 0x30c2, 0x20e3, 0x5004, 0x4025, 0x7046, 0x6067,
-    0x83b9, 0x9398, 0xa3fb, 0xb3da, 0xc33d, 0xd31c, 0xe37f, 0xf35e, 0x02b1, 0x1290, 0x22f3, 0x32d2,
-    0x4235, 0x5214, 0x6277, 0x7256, 0xb5ea, 0xa5cb, 0x95a8, 0x8589, 0xf56e, 0xe54f, 0xd52c, 0xc50d,
-    0x34e2, 0x24c3, 0x14a0, 0x0481, 0x7466, 0x6447, 0x5424, 0x4405, 0xa7db, 0xb7fa, 0x8799, 0x97b8,
-    0xe75f, 0xf77e, 0xc71d, 0xd73c, 0x26d3, 0x36f2, 0x0691, 0x16b0, 0x6657, 0x7676, 0x4615, 0x5634,
-    0xd94c, 0xc96d, 0xf90e, 0xe92f, 0x99c8, 0x89e9, 0xb98a, 0xa9ab, 0x5844, 0x4865, 0x7806, 0x6827,
-    0x18c0, 0x08e1, 0x3882, 0x28a3, 0xcb7d, 0xdb5c, 0xeb3f, 0xfb1e, 0x8bf9, 0x9bd8, 0xabbb, 0xbb9a,
-    0x4a75, 0x5a54, 0x6a37, 0x7a16, 0x0af1, 0x1ad0, 0x2ab3, 0x3a92, 0xfd2e, 0xed0f, 0xdd6c, 0xcd4d,
-    0xbdaa, 0xad8b, 0x9de8, 0x8dc9, 0x7c26, 0x6c07, 0x5c64, 0x4c45, 0x3ca2, 0x2c83, 0x1ce0, 0x0cc1,
-    0xef1f, 0xff3e, 0xcf5d, 0xdf7c, 0xaf9b, 0xbfba, 0x8fd9, 0x9ff8, 0x6e17, 0x7e36, 0x4e55, 0x5e74,
-    0x2e93, 0x3eb2, 0x0ed1, 0x1ef0,
-];
+//! CRC checksums for QR payloads
+//!
+//! `EMVCo` payloads use CRC-16/CCITT-FALSE, which [`calculate_crc16`],
+//! [`verify_crc`] and [`Crc16::new`] default to. Formats with other CRC-16
+//! parameters can be checksummed with a [`Crc16Algorithm`] built from
+//! [`Crc16Params`]; its lookup table is generated at compile time when the
+//! algorithm is a `static` or `const`.
+//!
+//! ```
+//! use ethqr_gen::crc::{Checksum, Crc16, Crc16Algorithm, Crc16Params};
+//!
+//! static LEGACY: Crc16Algorithm = Crc16Algorithm::new(Crc16Params {
+//!     poly: 0x8005,
+//!     init: 0xFFFF,
+//!     reflect: true,
+//!     xorout: 0x0000,
+//! });
+//!
+//! assert_eq!(Crc16::with_algorithm(&LEGACY).digest(b"123456789"), 0x4B37);
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hasher;
+
+use crate::tags;
+
+/// A checksum that can be computed incrementally
+pub trait Checksum {
+    /// The finished checksum
+    type Output;
+
+    /// Feed `bytes` into the checksum
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Checksum of the bytes fed so far
+    fn value(&self) -> Self::Output;
+
+    /// Discard the bytes fed so far
+    fn reset(&mut self);
+
+    /// Feed `bytes` and return the resulting checksum
+    fn digest(mut self, bytes: &[u8]) -> Self::Output
+    where
+        Self: Sized,
+    {
+        self.update(bytes);
+        self.value()
+    }
+}
+
+/// Parameters of a CRC-16 algorithm
+///
+/// `reflect` processes bytes least-significant bit first and reflects the
+/// result, as in the Rocksoft model with `refin == refout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Crc16Params {
+    pub poly: u16,
+    pub init: u16,
+    pub reflect: bool,
+    pub xorout: u16,
+}
+
+impl Crc16Params {
+    /// CRC-16/CCITT-FALSE, required by `EMVCo`
+    pub const CCITT_FALSE: Self = Self {
+        poly: 0x1021,
+        init: 0xFFFF,
+        reflect: false,
+        xorout: 0x0000,
+    };
+
+    /// CRC-16/XMODEM
+    pub const XMODEM: Self = Self {
+        poly: 0x1021,
+        init: 0x0000,
+        reflect: false,
+        xorout: 0x0000,
+    };
+
+    /// CRC-16/KERMIT
+    pub const KERMIT: Self = Self {
+        poly: 0x1021,
+        init: 0x0000,
+        reflect: true,
+        xorout: 0x0000,
+    };
+
+    /// CRC-16/IBM-SDLC, also known as X.25
+    pub const IBM_SDLC: Self = Self {
+        poly: 0x1021,
+        init: 0xFFFF,
+        reflect: true,
+        xorout: 0xFFFF,
+    };
+
+    /// CRC-16/ARC
+    pub const ARC: Self = Self {
+        poly: 0x8005,
+        init: 0x0000,
+        reflect: true,
+        xorout: 0x0000,
+    };
+
+    /// CRC-16/MODBUS
+    pub const MODBUS: Self = Self {
+        poly: 0x8005,
+        init: 0xFFFF,
+        reflect: true,
+        xorout: 0x0000,
+    };
+
+    /// Generate the slice-by-8 lookup tables for these parameters
+    ///
+    /// `tables[0]` is the byte-wise table from [`Crc16Params::table`];
+    /// `tables[k]` gives the effect of a byte followed by `k` zero bytes.
+    #[must_use]
+    pub const fn slice8_tables(&self) -> [[u16; 256]; 8] {
+        let mut tables = [[0u16; 256]; 8];
+        tables[0] = self.table();
+
+        let mut k = 1;
+        while k < 8 {
+            let mut i = 0;
+            while i < 256 {
+                let prev = tables[k - 1][i];
+                tables[k][i] = if self.reflect {
+                    (prev >> 8) ^ tables[0][(prev & 0xFF) as usize]
+                } else {
+                    (prev << 8) ^ tables[0][(prev >> 8) as usize]
+                };
+                i += 1;
+            }
+            k += 1;
+        }
+
+        tables
+    }
+
+    /// Generate the byte-wise lookup table for these parameters
+    #[must_use]
+    pub const fn table(&self) -> [u16; 256] {
+        let mut table = [0u16; 256];
+        let poly = if self.reflect {
+            self.poly.reverse_bits()
+        } else {
+            self.poly
+        };
+
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = if self.reflect {
+                i as u16
+            } else {
+                (i as u16) << 8
+            };
+
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if self.reflect {
+                    if crc & 1 == 0 {
+                        crc >> 1
+                    } else {
+                        (crc >> 1) ^ poly
+                    }
+                } else if crc & 0x8000 == 0 {
+                    crc << 1
+                } else {
+                    (crc << 1) ^ poly
+                };
+                bit += 1;
+            }
+
+            table[i] = crc;
+            i += 1;
+        }
+
+        table
+    }
+}
+
+/// A CRC-16 algorithm with its precomputed slice-by-8 lookup tables
+#[derive(Clone)]
+pub struct Crc16Algorithm {
+    params: Crc16Params,
+    tables: [[u16; 256]; 8],
+}
+
+impl Crc16Algorithm {
+    /// Build the algorithm, generating its tables (at compile time in a
+    /// `const` or `static`)
+    #[must_use]
+    pub const fn new(params: Crc16Params) -> Self {
+        Self {
+            params,
+            tables: params.slice8_tables(),
+        }
+    }
+
+    /// Parameters of this algorithm
+    #[must_use]
+    pub const fn params(&self) -> &Crc16Params {
+        &self.params
+    }
+
+    /// Register value before any bytes are fed
+    const fn initial_state(&self) -> u16 {
+        if self.params.reflect {
+            self.params.init.reverse_bits()
+        } else {
+            self.params.init
+        }
+    }
+}
+
+impl fmt::Debug for Crc16Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Crc16Algorithm")
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Crc16Algorithm {
+    fn eq(&self, other: &Self) -> bool {
+        // The tables are derived from the parameters
+        self.params == other.params
+    }
+}
+
+impl Eq for Crc16Algorithm {}
+
+/// CRC-16/CCITT-FALSE as used by `EMVCo` payloads
+pub static CRC16_CCITT_FALSE: Crc16Algorithm = Crc16Algorithm::new(Crc16Params::CCITT_FALSE);
+
+/// Incremental CRC-16 hasher, CRC-16/CCITT-FALSE unless built with
+/// [`Crc16::with_algorithm`]
+///
+/// Feed bytes with [`Crc16::update`], or through [`core::hash::Hasher`],
+/// [`core::fmt::Write`] or (with `std`) [`std::io::Write`], then read the
+/// checksum with [`Crc16::value`] or [`Crc16::to_hex`].
+///
+/// ```
+/// use core::fmt::Write;
+/// use ethqr_gen::crc::{Crc16, calculate_crc16};
+///
+/// let mut crc = Crc16::new();
+/// crc.update(b"000201");
+/// write!(crc, "5802{}", "ET")?;
+/// crc.update(b"6304");
+///
+/// let expected = calculate_crc16("0002015802ET6304");
+/// assert_eq!(format!("{:04X}", crc.value()), expected);
+/// assert_eq!(&crc.to_hex(), expected.as_bytes());
+/// # Ok::<(), core::fmt::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc16<'a> {
+    algorithm: &'a Crc16Algorithm,
+    state: u16,
+}
+
+impl Crc16<'static> {
+    /// Start a CRC-16/CCITT-FALSE checksum, as required by `EMVCo`
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_algorithm(&CRC16_CCITT_FALSE)
+    }
+}
+
+impl<'a> Crc16<'a> {
+    /// Start a checksum using `algorithm`, which may be built at runtime
+    #[must_use]
+    pub const fn with_algorithm(algorithm: &'a Crc16Algorithm) -> Self {
+        Self {
+            algorithm,
+            state: algorithm.initial_state(),
+        }
+    }
+
+    /// Algorithm used by this checksum
+    #[must_use]
+    pub const fn algorithm(&self) -> &'a Crc16Algorithm {
+        self.algorithm
+    }
+
+    /// Feed `bytes` into the checksum
+    ///
+    /// Bytes are consumed eight at a time using slice-by-8 tables, with a
+    /// byte-wise loop for the remainder.
+    pub fn update(&mut self, bytes: &[u8]) {
+        let [t0, t1, t2, t3, t4, t5, t6, t7] = &self.algorithm.tables;
+        let mut chunks = bytes.chunks_exact(8);
+        let [lo, hi] = self.state.to_le_bytes();
+
+        // In the reflected form the low register byte meets the first data byte
+        let (mut first, mut second) = if self.algorithm.params.reflect {
+            (lo, hi)
+        } else {
+            (hi, lo)
+        };
+
+        for chunk in &mut chunks {
+            let crc = t7[usize::from(chunk[0] ^ first)]
+                ^ t6[usize::from(chunk[1] ^ second)]
+                ^ t5[usize::from(chunk[2])]
+                ^ t4[usize::from(chunk[3])]
+                ^ t3[usize::from(chunk[4])]
+                ^ t2[usize::from(chunk[5])]
+                ^ t1[usize::from(chunk[6])]
+                ^ t0[usize::from(chunk[7])];
+            let [lo, hi] = crc.to_le_bytes();
+            (first, second) = if self.algorithm.params.reflect {
+                (lo, hi)
+            } else {
+                (hi, lo)
+            };
+        }
+
+        self.state = if self.algorithm.params.reflect {
+            u16::from_le_bytes([first, second])
+        } else {
+            u16::from_be_bytes([first, second])
+        };
+
+        if self.algorithm.params.reflect {
+            for &byte in chunks.remainder() {
+                let table_index = (self.state ^ u16::from(byte)) & 0xFF;
+                self.state = (self.state >> 8) ^ t0[table_index as usize];
+            }
+        } else {
+            for &byte in chunks.remainder() {
+                let table_index = ((self.state >> 8) ^ u16::from(byte)) & 0xFF;
+                self.state = (self.state << 8) ^ t0[table_index as usize];
+            }
+        }
+    }
+
+    /// Checksum of the bytes fed so far
+    #[must_use]
+    pub const fn value(&self) -> u16 {
+        self.state ^ self.algorithm.params.xorout
+    }
+
+    /// Discard the bytes fed so far
+    pub fn reset(&mut self) {
+        self.state = self.algorithm.initial_state();
+    }
+
+    /// Checksum as four uppercase hexadecimal ASCII digits
+    #[must_use]
+    pub const fn to_hex(&self) -> [u8; 4] {
+        const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+        let crc = self.value() as usize;
+        [
+            HEX_DIGITS[(crc >> 12) & 0xF],
+            HEX_DIGITS[(crc >> 8) & 0xF],
+            HEX_DIGITS[(crc >> 4) & 0xF],
+            HEX_DIGITS[crc & 0xF],
+        ]
+    }
+}
+
+impl Checksum for Crc16<'_> {
+    type Output = u16;
+
+    fn update(&mut self, bytes: &[u8]) {
+        Crc16::update(self, bytes);
+    }
+
+    fn value(&self) -> u16 {
+        Crc16::value(self)
+    }
+
+    fn reset(&mut self) {
+        Crc16::reset(self);
+    }
+}
+
+impl Default for Crc16<'_> {
+    fn default() -> Self {
+        Crc16::new()
+    }
+}
+
+impl Hasher for Crc16<'_> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        u64::from(self.value())
+    }
+}
+
+impl fmt::Write for Crc16<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::io::Write for Crc16<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
 
 /// Calculate CRC-16-CCITT checksum according to `EMVCo` specification
 ///
@@ -45,21 +435,134 @@ const CRC16_CCITT_TABLE: [u16; 256] = [
 /// ```
 #[must_use]
 pub fn calculate_crc16(data: &str) -> String {
-    let mut crc: u16 = 0xFFFF;
+    let mut crc = Crc16::new();
+    crc.update(data.as_bytes());
+
+    // EMVCo specification requires the CRC to be formatted as 4-digit uppercase hex
+    format!("{:04X}", crc.value())
+}
+
+/// Reason a payload failed CRC verification
+///
+/// Serialised with a `type` field, e.g. `{"type": "mismatch", "expected":
+/// "8622", "found": "80D3"}`; CRC values are four uppercase hex digits as in
+/// the payload.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum CrcError {
+    #[error("malformed TLV at offset {offset}")]
+    Malformed { offset: usize },
+
+    #[error("payload does not end with a CRC tag (63)")]
+    MissingTag,
+
+    #[error("CRC tag has length {length}, expected 4")]
+    InvalidLength { length: usize },
+
+    #[error("CRC value '{value}' is not four hexadecimal digits")]
+    InvalidDigits { value: String },
+
+    #[error("CRC mismatch: expected {expected:04X}, found {found:04X}")]
+    Mismatch {
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_hex"))]
+        expected: u16,
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_hex"))]
+        found: u16,
+    },
+}
+
+#[cfg(feature = "serde")]
+fn serialize_hex<S: serde::Serializer>(crc: &u16, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{crc:04X}"))
+}
 
-    for byte in data.bytes() {
-        let table_index = ((crc >> 8) ^ u16::from(byte)) & 0xFF;
-        crc = (crc << 8) ^ CRC16_CCITT_TABLE[table_index as usize];
+/// Check the CRC of a QR code string according to `EMVCo` specification
+///
+/// The payload is walked tag by tag so that the CRC is taken from tag 63 only
+/// when it is the final root tag. Any input, including non-ASCII text, is
+/// handled without panicking.
+///
+/// # Examples
+///
+/// ```
+/// use ethqr_gen::crc::{CrcError, check_crc};
+///
+/// let qr_string = "0002010102115204599953032305802ET5911Coffee Shop6011Addis Ababa630480D3";
+/// assert_eq!(check_crc(qr_string), Ok(()));
+///
+/// let tampered = qr_string.replace("Coffee", "Coffea");
+/// assert_eq!(
+///     check_crc(&tampered),
+///     Err(CrcError::Mismatch { expected: 0x8622, found: 0x80D3 })
+/// );
+/// ```
+///
+/// # Errors
+/// Returns a [`CrcError`] describing why the payload is malformed or its CRC
+/// does not match.
+pub fn check_crc(qr_string: &str) -> Result<(), CrcError> {
+    let bytes = qr_string.as_bytes();
+    let mut offset = 0;
+    let mut last = None;
+
+    while offset < bytes.len() {
+        let malformed = CrcError::Malformed { offset };
+        let header = bytes.get(offset..offset + 4).ok_or(malformed.clone())?;
+        if !header[..2].is_ascii() || !header[2..].iter().all(u8::is_ascii_digit) {
+            return Err(malformed);
+        }
+
+        let length = usize::from(header[2] - b'0') * 10 + usize::from(header[3] - b'0');
+        let end = offset + 4 + length;
+        if end > bytes.len() {
+            return Err(malformed);
+        }
+
+        last = Some((offset, &header[..2], length));
+        offset = end;
     }
 
-    // EMVCo specification requires the CRC to be formatted as 4-digit uppercase hex
-    format!("{crc:04X}")
+    let Some((crc_start, id, length)) = last else {
+        return Err(CrcError::MissingTag);
+    };
+    if id != tags::CRC.as_bytes() {
+        return Err(CrcError::MissingTag);
+    }
+    if length != 4 {
+        return Err(CrcError::InvalidLength { length });
+    }
+
+    // Four ASCII hex digits are valid UTF-8 and parse as a u16
+    let value = &bytes[crc_start + 4..];
+    let found = core::str::from_utf8(value)
+        .ok()
+        .filter(|value| value.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|value| u16::from_str_radix(value, 16).ok())
+        .ok_or_else(|| CrcError::InvalidDigits {
+            value: String::from_utf8_lossy(value).into_owned(),
+        })?;
+
+    // Calculate CRC for everything except the CRC value itself
+    let mut expected = Crc16::new();
+    expected.update(&bytes[..crc_start + 4]);
+
+    if found == expected.value() {
+        Ok(())
+    } else {
+        Err(CrcError::Mismatch {
+            expected: expected.value(),
+            found,
+        })
+    }
 }
 
 /// Verify CRC of QR code string according to `EMVCo` specification
 ///
-/// This function verifies that the CRC at the end of a QR code string matches
-/// the calculated CRC for the payload data.
+/// This is [`check_crc`] without the reason for a failure.
 ///
 /// # Arguments
 /// * `qr_string` - The complete QR code string including CRC
@@ -72,42 +575,50 @@ pub fn calculate_crc16(data: &str) -> String {
 /// ```
 /// use ethqr_gen::crc::verify_crc;
 ///
-/// let qr_string = "000201010212020140001234567890120415534512345678901252045999530358654041.005802CN5914BEST TRANSPORT6009GUANGZHOU6304A9AD";
+/// let qr_string = "0002010102115204599953032305802ET5911Coffee Shop6011Addis Ababa630480D3";
 /// assert!(verify_crc(qr_string));
 /// ```
 #[must_use]
 pub fn verify_crc(qr_string: &str) -> bool {
-    if qr_string.len() < 8 {
-        return false;
-    }
-
-    // CRC should be the last 8 characters: 6304XXXX (tag 63, length 04, 4-char CRC)
-    let crc_tag_start = qr_string.len().saturating_sub(8);
-
-    // Verify CRC tag format
-    if &qr_string[crc_tag_start..crc_tag_start + 4] != "6304" {
-        return false;
-    }
-
-    // Extract the provided CRC
-    let provided_crc = &qr_string[crc_tag_start + 4..];
-
-    // Calculate CRC for everything except the CRC value itself
-    let payload_for_crc = &qr_string[..crc_tag_start + 4];
-    let calculated_crc = calculate_crc16(payload_for_crc);
+    check_crc(qr_string).is_ok()
+}
 
-    // Compare CRCs (case-insensitive)
-    provided_crc.to_uppercase() == calculated_crc.to_uppercase()
+/// Verify the CRC of many payloads, returning the indices of those that fail
+///
+/// # Examples
+///
+/// ```
+/// use ethqr_gen::crc::verify_crc_bulk;
+///
+/// let archive = [
+///     "0002010102115204599953032305802ET5911Coffee Shop6011Addis Ababa630480D3",
+///     "0002010102115204599953032305802ET5911Coffee Shop6011Addis Ababa630480D4",
+/// ];
+/// assert_eq!(verify_crc_bulk(archive), [1]);
+/// ```
+#[must_use]
+pub fn verify_crc_bulk<I>(payloads: I) -> Vec<usize>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    payloads
+        .into_iter()
+        .enumerate()
+        .filter(|(_, payload)| !verify_crc(payload.as_ref()))
+        .map(|(index, _)| index)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     #[test]
     fn test_crc_calculation() {
-        // Test with known EMVCo example data
-        let test_data = "000201010212020140001234567890120415534512345678901252045999530358654041.005802CN5914BEST TRANSPORT6009GUANGZHOU6304";
+        // Test with a well-formed payload
+        let test_data = "0002010102115204599953032305802ET5911Coffee Shop6011Addis Ababa6304";
         let crc = calculate_crc16(test_data);
 
         // Verify CRC format
@@ -122,7 +633,7 @@ mod tests {
     #[test]
     fn test_crc_verification() {
         // Test with simple data
-        let payload = "00020101121502014000123456789012345260116304";
+        let payload = "00020101021115120140001234566304";
         let crc = calculate_crc16(payload);
         let complete_qr = format!("{payload}{crc}");
 
@@ -136,6 +647,171 @@ mod tests {
         assert!(!verify_crc(&corrupted));
     }
 
+    #[test]
+    fn test_check_crc_is_structural() {
+        let payload = "0002010102115204599953032305802ET5911Coffee Shop6011Addis Ababa630480D3";
+        assert_eq!(check_crc(payload), Ok(()));
+        assert_eq!(check_crc(&payload.replace("80D3", "80d3")), Ok(()));
+
+        // "6304" and a valid CRC hidden inside the merchant name
+        let body = "0002010102115204599953032305802ET6011Addis Ababa";
+        let inner = format!("{body}6304{}", calculate_crc16(&format!("{body}6304")));
+        let hidden = format!("59{:02}{inner}", inner.len());
+        assert!(verify_crc(&inner));
+        assert_eq!(check_crc(&hidden), Err(CrcError::MissingTag));
+
+        // CRC followed by another root tag
+        assert_eq!(
+            check_crc(&format!("{payload}5802ET")),
+            Err(CrcError::MissingTag)
+        );
+        assert_eq!(
+            check_crc(&payload.replace("80D3", "80D4")),
+            Err(CrcError::Mismatch {
+                expected: 0x80D3,
+                found: 0x80D4
+            })
+        );
+        assert_eq!(
+            check_crc(&payload.replace("80D3", "80DZ")),
+            Err(CrcError::InvalidDigits {
+                value: "80DZ".to_string()
+            })
+        );
+        assert_eq!(
+            check_crc("0002016305ABCDE"),
+            Err(CrcError::InvalidLength { length: 5 })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_crc_error_serialises_hex() {
+        let error = CrcError::Mismatch {
+            expected: 0x80D3,
+            found: 0x000A,
+        };
+        assert_eq!(
+            serde_json::to_value(error).unwrap(),
+            serde_json::json!({ "type": "mismatch", "expected": "80D3", "found": "000A" })
+        );
+    }
+
+    #[test]
+    fn test_check_crc_never_panics() {
+        for input in [
+            "",
+            "é",
+            "00020",
+            "0002é",
+            "0003é",
+            "é63041234",
+            "0002016304ÉÉ",
+            "5903ééé6304ABCD",
+            "0002016304€A",
+        ] {
+            let _ = check_crc(input);
+        }
+        assert_eq!(check_crc("0003é"), Err(CrcError::Malformed { offset: 0 }));
+        assert_eq!(
+            check_crc("0002016305€A"),
+            Err(CrcError::Malformed { offset: 6 })
+        );
+        assert_eq!(
+            check_crc("0002016304€A"),
+            Err(CrcError::InvalidDigits {
+                value: "€A".to_string()
+            })
+        );
+        assert!(matches!(
+            check_crc("0002016304ÉÉ"),
+            Err(CrcError::InvalidDigits { .. })
+        ));
+    }
+
+    #[test]
+    fn test_incremental_matches_one_shot() {
+        let data = "000201010212020140001234567890120415534512345678901252045999530358654041.005802CN5914BEST TRANSPORT6009GUANGZHOU6304";
+
+        let mut crc = Crc16::new();
+        for chunk in data.as_bytes().chunks(7) {
+            crc.update(chunk);
+        }
+        assert_eq!(&crc.to_hex(), b"A9AD");
+        assert_eq!(crc.finish(), 0xA9AD);
+
+        let mut hasher = Crc16::default();
+        hasher.write(data.as_bytes());
+        assert_eq!(hasher, crc);
+    }
+
+    #[test]
+    fn test_generated_table_matches_ccitt() {
+        let table = Crc16Params::CCITT_FALSE.table();
+        assert_eq!(table[1], 0x1021);
+        assert_eq!(table[128], 0x9188);
+        assert_eq!(table[255], 0x1EF0);
+    }
+
+    #[test]
+    fn test_parameterised_check_values() {
+        static XMODEM: Crc16Algorithm = Crc16Algorithm::new(Crc16Params::XMODEM);
+        static KERMIT: Crc16Algorithm = Crc16Algorithm::new(Crc16Params::KERMIT);
+        static IBM_SDLC: Crc16Algorithm = Crc16Algorithm::new(Crc16Params::IBM_SDLC);
+        static ARC: Crc16Algorithm = Crc16Algorithm::new(Crc16Params::ARC);
+        static MODBUS: Crc16Algorithm = Crc16Algorithm::new(Crc16Params::MODBUS);
+
+        // Standard check values over "123456789"
+        let check = |algorithm| Crc16::with_algorithm(algorithm).digest(b"123456789");
+        assert_eq!(check(&CRC16_CCITT_FALSE), 0x29B1);
+        assert_eq!(check(&XMODEM), 0x31C3);
+        assert_eq!(check(&KERMIT), 0x2189);
+        assert_eq!(check(&IBM_SDLC), 0x906E);
+        assert_eq!(check(&ARC), 0xBB3D);
+        assert_eq!(check(&MODBUS), 0x4B37);
+
+        let mut crc = Crc16::with_algorithm(&IBM_SDLC);
+        crc.update(b"garbage");
+        crc.reset();
+        crc.update(b"123456789");
+        assert_eq!(&crc.to_hex(), b"906E");
+
+        // Algorithms built at runtime are borrowed, not required to be static
+        let algorithm = Crc16Algorithm::new(Crc16Params {
+            init: 0x1D0F,
+            ..Crc16Params::XMODEM
+        });
+        assert_eq!(
+            Crc16::with_algorithm(&algorithm).digest(b"123456789"),
+            0xE5CC
+        );
+    }
+
+    #[test]
+    fn test_slice_by_8_matches_bytewise() {
+        static KERMIT: Crc16Algorithm = Crc16Algorithm::new(Crc16Params::KERMIT);
+
+        let data: Vec<u8> = (0..=255u8).cycle().take(700).collect();
+        for algorithm in [&CRC16_CCITT_FALSE, &KERMIT] {
+            let params = algorithm.params();
+            let table = params.table();
+            for len in [0, 1, 7, 8, 9, 15, 16, 17, 63, 512, 700] {
+                let mut expected = Crc16::with_algorithm(algorithm).state;
+                for &byte in &data[..len] {
+                    expected = if params.reflect {
+                        (expected >> 8) ^ table[usize::from((expected as u8) ^ byte)]
+                    } else {
+                        (expected << 8) ^ table[usize::from(((expected >> 8) as u8) ^ byte)]
+                    };
+                }
+
+                let mut crc = Crc16::with_algorithm(algorithm);
+                crc.update(&data[..len]);
+                assert_eq!(crc.state, expected, "length {len}");
+            }
+        }
+    }
+
     #[test]
     fn test_empty_and_invalid_input() {
         // Empty string should fail
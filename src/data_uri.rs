@@ -0,0 +1,106 @@
+//! Data URI output for web embedding
+//!
+//! Produces `data:` URIs that can be placed directly in the `src` attribute of
+//! an HTML `<img>` tag or inlined in email templates.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::{QRBuilder, fields::SchemeConfig};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let uri = QRBuilder::new()
+//!     .merchant_name("Coffee Shop")
+//!     .merchant_city("Addis Ababa")
+//!     .merchant_category_code("5812")
+//!     .add_scheme(SchemeConfig::visa("4111111111111111"))
+//!     .build_svg_data_uri(256)?;
+//!
+//! let html = format!(r#"<img src="{uri}" alt="Pay with QR">"#);
+//! # assert!(html.starts_with(r#"<img src="data:image/svg+xml,"#));
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Build a `data:image/svg+xml` URI from SVG markup
+///
+/// The markup is percent-encoded rather than base64-encoded, which keeps the
+/// URI shorter and safe to place inside a double-quoted HTML attribute.
+#[must_use]
+pub fn svg_data_uri(svg: &str) -> String {
+    let mut uri = String::with_capacity(svg.len() + 32);
+    uri.push_str("data:image/svg+xml,");
+
+    for byte in svg.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'~'
+            | b'!'
+            | b'$'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b'+'
+            | b','
+            | b';'
+            | b'='
+            | b':'
+            | b'@'
+            | b'/'
+            | b'?' => uri.push(char::from(byte)),
+            // Writing to a String cannot fail
+            _ => {
+                let _ = write!(uri, "%{byte:02X}");
+            }
+        }
+    }
+
+    uri
+}
+
+/// Build a base64 `data:` URI for binary content of the given MIME type
+#[cfg(feature = "qr-image")]
+#[must_use]
+pub fn base64_data_uri(mime_type: &str, bytes: &[u8]) -> String {
+    use alloc::format;
+    use base64::Engine;
+
+    format!(
+        "data:{mime_type};base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_data_uri_escaping() {
+        let uri = svg_data_uri(r##"<svg width="1"><path fill="#000" d="M0 0"/></svg>"##);
+
+        assert_eq!(
+            uri,
+            "data:image/svg+xml,%3Csvg%20width=%221%22%3E%3Cpath%20fill=%22%23000%22%20d=%22M0%200%22/%3E%3C/svg%3E"
+        );
+        assert!(!uri.contains('"') && !uri.contains('#'));
+    }
+
+    #[cfg(feature = "qr-image")]
+    #[test]
+    fn test_base64_data_uri() {
+        assert_eq!(
+            base64_data_uri("image/png", b"\x89PNG"),
+            "data:image/png;base64,iVBORw=="
+        );
+    }
+}
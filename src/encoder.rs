@@ -0,0 +1,265 @@
+//! Allocation-free payload encoding
+//!
+//! [`PayloadWriter`] writes TLV fields straight into a caller-supplied byte
+//! buffer and keeps the CRC register up to date as bytes go in, so finishing
+//! a payload only has to append the `6304` tag and four hex digits. It is
+//! what [`QRBuilder::encode_into`](crate::QRBuilder::encode_into) and
+//! [`QRBuilder::build_payload`](crate::QRBuilder::build_payload) use, and is
+//! available without the `std` feature.
+//!
+//! ```
+//! use ethqr_gen::encoder::PayloadWriter;
+//!
+//! let mut buf = [0u8; 64];
+//! let mut writer = PayloadWriter::new(&mut buf);
+//! writer.tag("00", "01")?;
+//! writer.template("62", &[("01", Some("INV-001")), ("05", None)])?;
+//! let payload = writer.finish()?;
+//!
+//! assert!(payload.starts_with("00020162110107INV-0016304"));
+//! assert!(ethqr_gen::crc::verify_crc(payload));
+//! # Ok::<(), ethqr_gen::error::QRError>(())
+//! ```
+
+use alloc::format;
+use core::fmt::{self, Write};
+
+use crate::constants;
+use crate::crc::Crc16;
+use crate::error::{QRError, Result};
+use crate::tags;
+
+/// Writes TLV fields into a byte buffer, computing the CRC incrementally
+///
+/// Writes never fail part-way: once the buffer is full the writer keeps
+/// counting bytes so [`PayloadWriter::finish`] can report the length the
+/// payload would have had.
+#[derive(Debug)]
+pub struct PayloadWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    crc: Crc16<'static>,
+}
+
+impl<'a> PayloadWriter<'a> {
+    /// Create a writer over `buf`
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            crc: Crc16::new(),
+        }
+    }
+
+    /// Continue after `prefix`, already encoded, whose CRC state is `crc`
+    pub(crate) fn resume(buf: &'a mut [u8], prefix: &[u8], crc: Crc16<'static>) -> Self {
+        let len = prefix.len();
+        if let Some(head) = buf.get_mut(..len) {
+            head.copy_from_slice(prefix);
+        }
+        Self { buf, len, crc }
+    }
+
+    /// CRC state over the bytes written so far
+    pub(crate) fn crc(&self) -> Crc16<'static> {
+        self.crc
+    }
+
+    /// Write already encoded fields
+    pub(crate) fn encoded(&mut self, fields: &str) {
+        self.push(fields.as_bytes());
+    }
+
+    /// Number of bytes written so far, including any that did not fit
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether nothing has been written yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bytes written so far that fit in the buffer
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len.min(self.buf.len())]
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        let end = self.len + bytes.len();
+        // Only advance the CRC while everything so far has fit
+        if end <= self.buf.len() {
+            self.buf[self.len..end].copy_from_slice(bytes);
+            self.crc.update(bytes);
+        }
+        self.len = end;
+    }
+
+    fn header(&mut self, id: &str, length: usize) -> Result<()> {
+        if length > constants::MAX_VALUE_LEN {
+            return Err(QRError::ValueTooLong {
+                field: format!("tag {id}"),
+                length,
+                max_length: constants::MAX_VALUE_LEN,
+            });
+        }
+
+        write!(self, "{id}{length:02}").map_err(|e| QRError::BuilderError {
+            message: format!("Failed to build QR code: {e}"),
+        })
+    }
+
+    /// Write a single `id` + length + `value` field
+    ///
+    /// # Errors
+    /// Returns an error if `value` is longer than 99 bytes or the field
+    /// cannot be formatted.
+    pub fn tag(&mut self, id: &str, value: &str) -> Result<()> {
+        self.header(id, value.len())?;
+        self.push(value.as_bytes());
+        Ok(())
+    }
+
+    /// Write a template field whose value is made of the given sub-tags
+    ///
+    /// Sub-tags whose value is `None` are skipped, and nothing is written if
+    /// every sub-tag is `None`.
+    ///
+    /// # Errors
+    /// Returns an error if the value or any sub-tag is longer than 99 bytes
+    /// or the field cannot be formatted.
+    pub fn template(&mut self, id: &str, sub_tags: &[(&str, Option<&str>)]) -> Result<()> {
+        let present = || {
+            sub_tags
+                .iter()
+                .filter_map(|&(id, value)| value.map(|value| (id, value)))
+        };
+
+        if present().next().is_none() {
+            return Ok(());
+        }
+
+        let length = present()
+            .map(|(id, value)| id.len() + 2 + value.len())
+            .sum();
+        self.header(id, length)?;
+        for (id, value) in present() {
+            self.tag(id, value)?;
+        }
+        Ok(())
+    }
+
+    /// Append the CRC field and return the finished payload
+    ///
+    /// # Errors
+    /// Returns [`QRError::PayloadTooLong`] if the payload exceeds
+    /// [`constants::MAX_QR_LENGTH`], or [`QRError::BuilderError`] if it does
+    /// not fit in the buffer.
+    pub fn finish(mut self) -> Result<&'a str> {
+        self.header(tags::CRC, 4)?;
+
+        let digits = self.crc.to_hex();
+        self.push(&digits);
+
+        if self.len > constants::MAX_QR_LENGTH {
+            return Err(QRError::PayloadTooLong { length: self.len });
+        }
+        if self.len > self.buf.len() {
+            return Err(QRError::BuilderError {
+                message: format!(
+                    "Buffer too small: payload needs {} bytes, buffer holds {}",
+                    self.len,
+                    self.buf.len()
+                ),
+            });
+        }
+
+        // Only whole `&str`s are ever copied in, so the contents are UTF-8
+        core::str::from_utf8(&self.buf[..self.len]).map_err(|e| QRError::BuilderError {
+            message: format!("Failed to build QR code: {e}"),
+        })
+    }
+}
+
+impl Write for PayloadWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::verify_crc;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_template_skips_missing_sub_tags() {
+        let mut buf = [0u8; 128];
+        let mut writer = PayloadWriter::new(&mut buf);
+        writer.tag("00", "01").unwrap();
+        writer
+            .template(
+                "28",
+                &[("00", Some("abc")), ("01", None), ("02", Some("12"))],
+            )
+            .unwrap();
+        writer.template("62", &[("01", None)]).unwrap();
+        let payload = writer.finish().unwrap();
+
+        assert!(payload.starts_with("00020128130003abc0202126304"));
+        assert!(verify_crc(payload));
+    }
+
+    #[test]
+    fn test_small_buffer_reports_needed_length() {
+        let mut buf = [0u8; 8];
+        let mut writer = PayloadWriter::new(&mut buf);
+        writer.tag("59", "Coffee Shop").unwrap();
+        assert_eq!(writer.len(), 15);
+
+        let err = writer.finish().unwrap_err();
+        assert!(matches!(err, QRError::BuilderError { .. }));
+        assert!(err.to_string().contains("23 bytes"));
+    }
+
+    #[test]
+    fn test_oversized_payload() {
+        let mut buf = [0u8; 1024];
+        let mut writer = PayloadWriter::new(&mut buf);
+        for _ in 0..6 {
+            writer.tag("62", &"x".repeat(90)).unwrap();
+        }
+        assert!(matches!(
+            writer.finish(),
+            Err(QRError::PayloadTooLong { length: 572 })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_values_over_99_bytes() {
+        let mut buf = [0u8; 1024];
+        let mut writer = PayloadWriter::new(&mut buf);
+        let label = "x".repeat(25);
+        let sub_tags: [(&str, Option<&str>); 5] =
+            ["01", "03", "05", "06", "07"].map(|id| (id, Some(label.as_str())));
+
+        assert!(matches!(
+            writer.template("62", &sub_tags),
+            Err(QRError::ValueTooLong {
+                length: 145,
+                max_length: 99,
+                ..
+            })
+        ));
+        assert!(matches!(
+            writer.tag("59", &"x".repeat(100)),
+            Err(QRError::ValueTooLong { length: 100, .. })
+        ));
+        writer.tag("59", &"x".repeat(99)).unwrap();
+    }
+}
@@ -1,3 +1,7 @@
+use alloc::string::String;
+
+use crate::crc::CrcError;
+
 /// Main error type for QR payment operations
 #[derive(Debug, thiserror::Error)]
 pub enum QRError {
@@ -7,6 +11,10 @@ pub enum QRError {
     #[error("CRC validation failed")]
     InvalidCRC,
 
+    /// CRC validation failed for the given reason
+    #[error("CRC validation failed: {reason}")]
+    CrcCheckFailed { reason: CrcError },
+
     #[error("Missing required field: {field}")]
     MissingField { field: String },
 
@@ -37,10 +45,54 @@ pub enum QRError {
 
     #[error("Image processing failed: {message}")]
     ImageError { message: String },
+
+    #[error("I/O error: {message}")]
+    IoError { message: String },
+
+    #[error("Insufficient colour contrast: {ratio:.2} < {min_ratio}")]
+    InsufficientContrast { ratio: f64, min_ratio: f64 },
+
+    #[error("QR code expired at {expires_at} (now {now})")]
+    Expired { expires_at: u64, now: u64 },
+
+    #[error("QR code not valid before {not_before} (now {now})")]
+    NotYetValid { not_before: u64, now: u64 },
 }
 
-pub type Result<T> = std::result::Result<T, QRError>;
+pub type Result<T> = core::result::Result<T, QRError>;
 
+impl QRError {
+    /// Stable machine-readable identifier for the error variant
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            QRError::InvalidFormat { .. } => "invalid_format",
+            // Both report the same failure; one carries the reason
+            QRError::InvalidCRC | QRError::CrcCheckFailed { .. } => "invalid_crc",
+            QRError::MissingField { .. } => "missing_field",
+            QRError::InvalidValue { .. } => "invalid_value",
+            QRError::ValueTooLong { .. } => "value_too_long",
+            QRError::UnsupportedScheme { .. } => "unsupported_scheme",
+            QRError::PayloadTooLong { .. } => "payload_too_long",
+            QRError::BuilderError { .. } => "builder_error",
+            QRError::ValidationError { .. } => "validation_error",
+            QRError::QRCodeError { .. } => "qr_code_error",
+            QRError::ImageError { .. } => "image_error",
+            QRError::IoError { .. } => "io_error",
+            QRError::InsufficientContrast { .. } => "insufficient_contrast",
+            QRError::Expired { .. } => "expired",
+            QRError::NotYetValid { .. } => "not_yet_valid",
+        }
+    }
+}
+
+impl From<CrcError> for QRError {
+    fn from(reason: CrcError) -> Self {
+        QRError::CrcCheckFailed { reason }
+    }
+}
+
+#[cfg(feature = "std")]
 impl From<qrcode::types::QrError> for QRError {
     fn from(err: qrcode::types::QrError) -> Self {
         QRError::QRCodeError {
@@ -49,6 +101,15 @@ impl From<qrcode::types::QrError> for QRError {
     }
 }
 
+#[cfg(feature = "std")]
+impl From<std::io::Error> for QRError {
+    fn from(err: std::io::Error) -> Self {
+        QRError::IoError {
+            message: err.to_string(),
+        }
+    }
+}
+
 #[cfg(feature = "qr-image")]
 impl From<image::ImageError> for QRError {
     fn from(err: image::ImageError) -> Self {
@@ -57,3 +118,27 @@ impl From<image::ImageError> for QRError {
         }
     }
 }
+
+#[cfg(feature = "qr-image")]
+impl From<png::EncodingError> for QRError {
+    fn from(err: png::EncodingError) -> Self {
+        QRError::ImageError {
+            message: err.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "batch")]
+impl From<csv::Error> for QRError {
+    fn from(err: csv::Error) -> Self {
+        if err.is_io_error() {
+            QRError::IoError {
+                message: err.to_string(),
+            }
+        } else {
+            QRError::InvalidFormat {
+                message: err.to_string(),
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! ESC/POS output for thermal receipt printers
+//!
+//! Encodes a payload into printer command bytes that can be written directly
+//! to a thermal printer, either using the printer's native QR code command
+//! (`GS ( k`) or as a raster bitmap (`GS v 0`) for printers without QR support.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::{QRBuilder, fields::SchemeConfig};
+//! use ethqr_gen::escpos::{Alignment, EscPosEncoder, EscPosMode, PaperWidth};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let payload = QRBuilder::new()
+//!     .merchant_name("Coffee Shop")
+//!     .merchant_city("Addis Ababa")
+//!     .merchant_category_code("5812")
+//!     .add_scheme(SchemeConfig::visa("4111111111111111"))
+//!     .transaction_amount("120.00")
+//!     .build_payload()?;
+//!
+//! let bytes = EscPosEncoder::new()
+//!     .mode(EscPosMode::Raster)
+//!     .paper_width(PaperWidth::Mm58)
+//!     .module_size(4)
+//!     .alignment(Alignment::Center)
+//!     .encode(&payload)?;
+//! # assert!(!bytes.is_empty());
+//! # Ok(())
+//! # }
+//! ```
+
+use qrcode::{Color, QrCode};
+
+use crate::error::{QRError, Result};
+
+const ESC: u8 = 0x1B;
+const GS: u8 = 0x1D;
+const LF: u8 = 0x0A;
+
+/// Largest module size accepted by the native `GS ( k` command
+pub const MAX_NATIVE_MODULE_SIZE: u8 = 16;
+
+/// Quiet zone added around raster images, in modules
+pub const RASTER_QUIET_ZONE: usize = 4;
+
+/// Printable paper width
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PaperWidth {
+    /// 58 mm paper (384 dots at 203 dpi)
+    Mm58,
+    /// 80 mm paper (576 dots at 203 dpi)
+    #[default]
+    Mm80,
+}
+
+impl PaperWidth {
+    /// Printable width in dots
+    #[must_use]
+    pub fn dots(self) -> usize {
+        match self {
+            PaperWidth::Mm58 => 384,
+            PaperWidth::Mm80 => 576,
+        }
+    }
+}
+
+/// Horizontal alignment of the printed code
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+impl Alignment {
+    fn code(self) -> u8 {
+        match self {
+            Alignment::Left => 0,
+            Alignment::Center => 1,
+            Alignment::Right => 2,
+        }
+    }
+}
+
+/// Command set used to print the QR code
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EscPosMode {
+    /// Printer-side QR generation using `GS ( k`
+    #[default]
+    Native,
+    /// Pre-rendered bitmap using `GS v 0`
+    Raster,
+}
+
+/// Encoder producing ESC/POS command bytes
+#[derive(Debug, Clone)]
+pub struct EscPosEncoder {
+    mode: EscPosMode,
+    module_size: u8,
+    alignment: Alignment,
+    paper_width: PaperWidth,
+    initialize: bool,
+}
+
+impl Default for EscPosEncoder {
+    fn default() -> Self {
+        Self {
+            mode: EscPosMode::default(),
+            module_size: 6,
+            alignment: Alignment::default(),
+            paper_width: PaperWidth::default(),
+            initialize: true,
+        }
+    }
+}
+
+impl EscPosEncoder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the command set used to print the code
+    pub fn mode(mut self, mode: EscPosMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the size of a single module in printer dots
+    pub fn module_size(mut self, module_size: u8) -> Self {
+        self.module_size = module_size;
+        self
+    }
+
+    /// Set horizontal alignment
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Set the paper width, used to bound raster images
+    pub fn paper_width(mut self, paper_width: PaperWidth) -> Self {
+        self.paper_width = paper_width;
+        self
+    }
+
+    /// Emit `ESC @` to reset the printer before the code (enabled by default)
+    pub fn initialize(mut self, initialize: bool) -> Self {
+        self.initialize = initialize;
+        self
+    }
+
+    /// Encode the payload as ESC/POS command bytes
+    ///
+    /// # Errors
+    /// Returns an error if the module size is out of range, the payload cannot
+    /// be encoded as a QR code, or the raster image is wider than the paper.
+    pub fn encode(&self, payload: &str) -> Result<Vec<u8>> {
+        if self.module_size == 0 {
+            return Err(QRError::InvalidValue {
+                field: "module_size".to_string(),
+                value: self.module_size.to_string(),
+            });
+        }
+
+        let mut bytes = Vec::new();
+
+        if self.initialize {
+            bytes.extend_from_slice(&[ESC, b'@']);
+        }
+        bytes.extend_from_slice(&[ESC, b'a', self.alignment.code()]);
+
+        match self.mode {
+            EscPosMode::Native => self.encode_native(payload, &mut bytes)?,
+            EscPosMode::Raster => self.encode_raster(payload, &mut bytes)?,
+        }
+
+        bytes.push(LF);
+
+        Ok(bytes)
+    }
+
+    fn encode_native(&self, payload: &str, bytes: &mut Vec<u8>) -> Result<()> {
+        if self.module_size > MAX_NATIVE_MODULE_SIZE {
+            return Err(QRError::InvalidValue {
+                field: "module_size".to_string(),
+                value: self.module_size.to_string(),
+            });
+        }
+
+        // Function 165: select model 2
+        bytes.extend_from_slice(&[GS, b'(', b'k', 4, 0, 49, 65, 50, 0]);
+        // Function 167: module size
+        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 67, self.module_size]);
+        // Function 169: error correction level M, matching `QrCode::new`
+        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 69, 49]);
+
+        // Function 180: store data in the symbol storage area
+        let [p_l, p_h] = u16::try_from(payload.len() + 3)
+            .map_err(|_| QRError::PayloadTooLong {
+                length: payload.len(),
+            })?
+            .to_le_bytes();
+        bytes.extend_from_slice(&[GS, b'(', b'k', p_l, p_h, 49, 80, 48]);
+        bytes.extend_from_slice(payload.as_bytes());
+
+        // Function 181: print the stored symbol
+        bytes.extend_from_slice(&[GS, b'(', b'k', 3, 0, 49, 81, 48]);
+
+        Ok(())
+    }
+
+    fn encode_raster(&self, payload: &str, bytes: &mut Vec<u8>) -> Result<()> {
+        let qr_code = QrCode::new(payload)?;
+        let modules = qr_code.width() + 2 * RASTER_QUIET_ZONE;
+        let scale = usize::from(self.module_size);
+        let size = modules * scale;
+
+        if size > self.paper_width.dots() {
+            return Err(QRError::ValueTooLong {
+                field: "raster_width".to_string(),
+                length: size,
+                max_length: self.paper_width.dots(),
+            });
+        }
+
+        let row_bytes = size.div_ceil(8);
+        let colors = qr_code.to_colors();
+        let is_dark = |x: usize, y: usize| -> bool {
+            let (x, y) = (x / scale, y / scale);
+            x >= RASTER_QUIET_ZONE
+                && y >= RASTER_QUIET_ZONE
+                && x < modules - RASTER_QUIET_ZONE
+                && y < modules - RASTER_QUIET_ZONE
+                && colors[(y - RASTER_QUIET_ZONE) * qr_code.width() + (x - RASTER_QUIET_ZONE)]
+                    == Color::Dark
+        };
+
+        // Dimensions are bounded by the paper width so they always fit in u16
+        let [x_l, x_h] = u16::try_from(row_bytes).unwrap_or(u16::MAX).to_le_bytes();
+        let [y_l, y_h] = u16::try_from(size).unwrap_or(u16::MAX).to_le_bytes();
+        bytes.extend_from_slice(&[GS, b'v', b'0', 0, x_l, x_h, y_l, y_h]);
+
+        for y in 0..size {
+            for chunk in 0..row_bytes {
+                let mut byte = 0u8;
+                for bit in 0..8 {
+                    let x = chunk * 8 + bit;
+                    if x < size && is_dark(x, y) {
+                        byte |= 0x80 >> bit;
+                    }
+                }
+                bytes.push(byte);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYLOAD: &str = "00020101021102164111111111111111520458125303230580\
+2ET5911Coffee Shop6011Addis Ababa6304ABCD";
+
+    #[test]
+    fn test_native_encoding() {
+        let bytes = EscPosEncoder::new()
+            .module_size(8)
+            .alignment(Alignment::Right)
+            .encode(PAYLOAD)
+            .unwrap();
+
+        assert!(bytes.starts_with(&[ESC, b'@', ESC, b'a', 2]));
+        assert!(
+            bytes
+                .windows(8)
+                .any(|w| w == [GS, b'(', b'k', 3, 0, 49, 67, 8])
+        );
+
+        let len = PAYLOAD.len() + 3;
+        let store = [
+            GS,
+            b'(',
+            b'k',
+            (len & 0xFF) as u8,
+            (len >> 8) as u8,
+            49,
+            80,
+            48,
+        ];
+        let start = bytes.windows(8).position(|w| w == store).unwrap() + 8;
+        assert_eq!(&bytes[start..start + PAYLOAD.len()], PAYLOAD.as_bytes());
+
+        assert!(bytes.ends_with(&[GS, b'(', b'k', 3, 0, 49, 81, 48, LF]));
+    }
+
+    #[test]
+    fn test_raster_encoding() {
+        let bytes = EscPosEncoder::new()
+            .mode(EscPosMode::Raster)
+            .module_size(3)
+            .initialize(false)
+            .encode(PAYLOAD)
+            .unwrap();
+
+        let modules = QrCode::new(PAYLOAD).unwrap().width() + 2 * RASTER_QUIET_ZONE;
+        let size = modules * 3;
+        let row_bytes = size.div_ceil(8);
+
+        assert_eq!(&bytes[..7], &[ESC, b'a', 1, GS, b'v', b'0', 0]);
+        assert_eq!(
+            usize::from(bytes[7]) + (usize::from(bytes[8]) << 8),
+            row_bytes
+        );
+        assert_eq!(usize::from(bytes[9]) + (usize::from(bytes[10]) << 8), size);
+        assert_eq!(bytes.len(), 11 + row_bytes * size + 1);
+
+        // First row lies in the quiet zone and must be blank
+        assert!(bytes[11..11 + row_bytes].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_invalid_module_size() {
+        assert!(EscPosEncoder::new().module_size(0).encode(PAYLOAD).is_err());
+        assert!(
+            EscPosEncoder::new()
+                .module_size(17)
+                .encode(PAYLOAD)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_raster_wider_than_paper() {
+        let result = EscPosEncoder::new()
+            .mode(EscPosMode::Raster)
+            .paper_width(PaperWidth::Mm58)
+            .module_size(16)
+            .encode(PAYLOAD);
+
+        assert!(matches!(result, Err(QRError::ValueTooLong { .. })));
+    }
+}
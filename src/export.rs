@@ -0,0 +1,219 @@
+//! In-memory encoding of QR code images
+//!
+//! Encodes rendered QR code images to PNG, JPEG or WebP bytes without going
+//! through the filesystem, either into a buffer or streamed into any
+//! [`Write`] implementation such as an HTTP response body.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::{QRBuilder, fields::SchemeConfig};
+//! use ethqr_gen::export::{ImageEncoder, ImageFormat};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let png = QRBuilder::new()
+//!     .merchant_name("Coffee Shop")
+//!     .merchant_city("Addis Ababa")
+//!     .merchant_category_code("5812")
+//!     .add_scheme(SchemeConfig::visa("4111111111111111"))
+//!     .build_image_bytes(&ImageEncoder::new(ImageFormat::Png).dpi(300))?;
+//!
+//! assert!(png.starts_with(b"\x89PNG"));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::Write;
+
+use image::DynamicImage;
+use image::codecs::jpeg::{JpegEncoder, PixelDensity};
+use image::codecs::webp::WebPEncoder;
+
+use crate::error::{QRError, Result};
+
+/// Metres per inch, used to convert DPI to PNG pixels-per-metre
+const METERS_PER_INCH: f64 = 0.0254;
+
+/// Output image format
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    /// Baseline JPEG with quality from 1 to 100
+    Jpeg { quality: u8 },
+    /// Lossless WebP
+    WebP,
+}
+
+impl ImageFormat {
+    /// MIME type of the encoded image
+    #[must_use]
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg { .. } => "image/jpeg",
+            ImageFormat::WebP => "image/webp",
+        }
+    }
+}
+
+/// Encoder turning images into bytes of a given format
+#[derive(Debug, Default, Clone)]
+pub struct ImageEncoder {
+    format: ImageFormat,
+    dpi: Option<u16>,
+}
+
+impl ImageEncoder {
+    #[must_use]
+    pub fn new(format: ImageFormat) -> Self {
+        Self { format, dpi: None }
+    }
+
+    /// Set the resolution stored in the image metadata
+    ///
+    /// Written as a `pHYs` chunk for PNG and JFIF density for JPEG. WebP has
+    /// no resolution field, so the value is ignored for that format.
+    pub fn dpi(mut self, dpi: u16) -> Self {
+        self.dpi = Some(dpi);
+        self
+    }
+
+    /// Output image format
+    #[must_use]
+    pub fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    /// Encode the image into a byte vector
+    ///
+    /// # Errors
+    /// Returns an error if the image cannot be encoded in the chosen format.
+    pub fn encode(&self, image: &DynamicImage) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.write_to(image, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Encode the image and stream it into `writer`
+    ///
+    /// # Errors
+    /// Returns an error if the image cannot be encoded in the chosen format or
+    /// the writer fails.
+    pub fn write_to<W: Write>(&self, image: &DynamicImage, writer: W) -> Result<()> {
+        match self.format {
+            ImageFormat::Png => self.write_png(image, writer),
+            ImageFormat::Jpeg { quality } => {
+                if !(1..=100).contains(&quality) {
+                    return Err(QRError::InvalidValue {
+                        field: "quality".to_string(),
+                        value: quality.to_string(),
+                    });
+                }
+
+                let mut encoder = JpegEncoder::new_with_quality(writer, quality);
+                if let Some(dpi) = self.dpi {
+                    encoder.set_pixel_density(PixelDensity::dpi(dpi));
+                }
+
+                // JPEG has no alpha channel
+                match image {
+                    DynamicImage::ImageLuma8(luma) => encoder.encode_image(luma)?,
+                    _ => encoder.encode_image(&image.to_rgb8())?,
+                }
+
+                Ok(())
+            }
+            ImageFormat::WebP => {
+                let encoder = WebPEncoder::new_lossless(writer);
+                match image {
+                    DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgba8(_) => {
+                        image.write_with_encoder(encoder)?;
+                    }
+                    _ => DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(encoder)?,
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    fn write_png<W: Write>(&self, image: &DynamicImage, writer: W) -> Result<()> {
+        let (color, data) = match image {
+            DynamicImage::ImageLuma8(luma) => (png::ColorType::Grayscale, luma.as_raw().clone()),
+            _ => (png::ColorType::Rgba, image.to_rgba8().into_raw()),
+        };
+
+        let mut encoder = png::Encoder::new(writer, image.width(), image.height());
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        if let Some(dpi) = self.dpi {
+            let ppm = (f64::from(dpi) / METERS_PER_INCH).round() as u32;
+            encoder.set_pixel_dims(Some(png::PixelDimensions {
+                xppu: ppm,
+                yppu: ppm,
+                unit: png::Unit::Meter,
+            }));
+        }
+
+        let mut png_writer = encoder.write_header()?;
+        png_writer.write_image_data(&data)?;
+        png_writer.finish()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Luma};
+
+    fn sample() -> DynamicImage {
+        DynamicImage::ImageLuma8(ImageBuffer::from_fn(8, 8, |x, y| {
+            Luma([if (x + y) % 2 == 0 { 0 } else { 255 }])
+        }))
+    }
+
+    #[test]
+    fn test_png_with_dpi() {
+        let bytes = ImageEncoder::new(ImageFormat::Png)
+            .dpi(300)
+            .encode(&sample())
+            .unwrap();
+
+        assert!(bytes.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        // pHYs chunk: 11811 pixels per metre on both axes, unit metre
+        let phys = bytes.windows(4).position(|w| w == b"pHYs").unwrap();
+        assert_eq!(&bytes[phys + 4..phys + 8], &11811u32.to_be_bytes());
+        assert_eq!(bytes[phys + 12], 1);
+
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(decoded.to_luma8(), sample().to_luma8());
+    }
+
+    #[test]
+    fn test_jpeg_and_webp() {
+        let jpeg = ImageEncoder::new(ImageFormat::Jpeg { quality: 90 })
+            .dpi(203)
+            .encode(&sample())
+            .unwrap();
+        assert!(jpeg.starts_with(&[0xFF, 0xD8]));
+        // JFIF density unit (inches) followed by 203 dpi on both axes
+        assert!(jpeg.windows(5).any(|w| w == [1, 0, 203, 0, 203]));
+
+        let webp = ImageEncoder::new(ImageFormat::WebP)
+            .encode(&sample())
+            .unwrap();
+        assert_eq!(&webp[..4], b"RIFF");
+        assert_eq!(&webp[8..12], b"WEBP");
+    }
+
+    #[test]
+    fn test_invalid_jpeg_quality() {
+        let result = ImageEncoder::new(ImageFormat::Jpeg { quality: 0 }).encode(&sample());
+        assert!(matches!(result, Err(QRError::InvalidValue { .. })));
+    }
+}
@@ -1,9 +1,18 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+
 use crate::EMVTag;
+use crate::encoder::PayloadWriter;
 use crate::error::{QRError, Result};
-use crate::tags;
+use crate::{constants, tags};
 
 /// Additional data fields (tag 62)
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
 pub struct AdditionalData {
     /// Bill/Invoice/Voucher number (tag 01)
     pub bill_number: Option<String>,
@@ -105,64 +114,296 @@ impl AdditionalData {
         self
     }
 
+    /// Apply every field set in `overrides` on top of these fields
+    #[must_use]
+    pub fn overlay(self, overrides: AdditionalData) -> Self {
+        AdditionalData {
+            bill_number: overrides.bill_number.or(self.bill_number),
+            mobile_number: overrides.mobile_number.or(self.mobile_number),
+            store_label: overrides.store_label.or(self.store_label),
+            loyalty_number: overrides.loyalty_number.or(self.loyalty_number),
+            reference_label: overrides.reference_label.or(self.reference_label),
+            customer_label: overrides.customer_label.or(self.customer_label),
+            terminal_number: overrides.terminal_number.or(self.terminal_number),
+            purpose: overrides.purpose.or(self.purpose),
+            additional_customer_data: overrides
+                .additional_customer_data
+                .or(self.additional_customer_data),
+            merchant_tax_id: overrides.merchant_tax_id.or(self.merchant_tax_id),
+            merchant_channel: overrides.merchant_channel.or(self.merchant_channel),
+            due_date: overrides.due_date.or(self.due_date),
+            amount_after_due_date: overrides
+                .amount_after_due_date
+                .or(self.amount_after_due_date),
+        }
+    }
+
+    pub(crate) fn sub_tags(&self) -> [(&str, Option<&str>); 13] {
+        [
+            ("01", self.bill_number.as_deref()),
+            ("02", self.mobile_number.as_deref()),
+            ("03", self.store_label.as_deref()),
+            ("04", self.loyalty_number.as_deref()),
+            ("05", self.reference_label.as_deref()),
+            ("06", self.customer_label.as_deref()),
+            ("07", self.terminal_number.as_deref()),
+            ("08", self.purpose.as_deref()),
+            ("09", self.additional_customer_data.as_deref()),
+            ("10", self.merchant_tax_id.as_deref()),
+            ("11", self.merchant_channel.as_deref()),
+            ("50", self.due_date.as_deref()),
+            ("51", self.amount_after_due_date.as_deref()),
+        ]
+    }
+
     /// Encode additional data as EMV tag
     pub fn encode(&self) -> Option<EMVTag> {
-        let mut sub_tags = Vec::new();
+        let value = self
+            .sub_tags()
+            .into_iter()
+            .filter_map(|(id, value)| value.map(|value| EMVTag::new(id, value).encode()))
+            .collect::<String>();
 
-        if let Some(ref value) = self.bill_number {
-            sub_tags.push(EMVTag::new("01", value));
-        }
-        if let Some(ref value) = self.mobile_number {
-            sub_tags.push(EMVTag::new("02", value));
-        }
-        if let Some(ref value) = self.store_label {
-            sub_tags.push(EMVTag::new("03", value));
+        if value.is_empty() {
+            None
+        } else {
+            Some(EMVTag::new(tags::ADDITIONAL_DATA, value))
         }
-        if let Some(ref value) = self.loyalty_number {
-            sub_tags.push(EMVTag::new("04", value));
+    }
+
+    /// Write additional data without allocating; nothing is written if empty
+    ///
+    /// # Errors
+    /// Returns an error if the field cannot be formatted.
+    pub fn write_to(&self, writer: &mut PayloadWriter<'_>) -> Result<()> {
+        writer.template(tags::ADDITIONAL_DATA, &self.sub_tags())
+    }
+}
+
+/// Merchant information in an alternate language (tag 64)
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct LanguageTemplate {
+    /// ISO 639 two-letter language code (tag 00)
+    pub language_preference: String,
+    /// Merchant name in the alternate language (tag 01)
+    pub merchant_name: String,
+    /// Merchant city in the alternate language (tag 02)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub merchant_city: Option<String>,
+}
+
+impl LanguageTemplate {
+    pub fn new(language_preference: impl Into<String>, merchant_name: impl Into<String>) -> Self {
+        Self {
+            language_preference: language_preference.into(),
+            merchant_name: merchant_name.into(),
+            merchant_city: None,
         }
-        if let Some(ref value) = self.reference_label {
-            sub_tags.push(EMVTag::new("05", value));
+    }
+
+    pub fn merchant_city(mut self, merchant_city: impl Into<String>) -> Self {
+        self.merchant_city = Some(merchant_city.into());
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.language_preference.len() != 2
+            || !self
+                .language_preference
+                .chars()
+                .all(|c| c.is_ascii_alphabetic())
+        {
+            return Err(QRError::InvalidValue {
+                field: "language_preference".to_string(),
+                value: self.language_preference.clone(),
+            });
         }
-        if let Some(ref value) = self.customer_label {
-            sub_tags.push(EMVTag::new("06", value));
+
+        if self.merchant_name.len() > constants::MAX_MERCHANT_NAME_LEN {
+            return Err(QRError::ValueTooLong {
+                field: "alternate_name".to_string(),
+                length: self.merchant_name.len(),
+                max_length: constants::MAX_MERCHANT_NAME_LEN,
+            });
         }
-        if let Some(ref value) = self.terminal_number {
-            sub_tags.push(EMVTag::new("07", value));
+
+        if let Some(ref city) = self.merchant_city
+            && city.len() > constants::MAX_MERCHANT_CITY_LEN
+        {
+            return Err(QRError::ValueTooLong {
+                field: "alternate_city".to_string(),
+                length: city.len(),
+                max_length: constants::MAX_MERCHANT_CITY_LEN,
+            });
         }
-        if let Some(ref value) = self.purpose {
-            sub_tags.push(EMVTag::new("08", value));
+
+        Ok(())
+    }
+
+    fn sub_tags(&self) -> [(&str, Option<&str>); 3] {
+        [
+            ("00", Some(self.language_preference.as_str())),
+            ("01", Some(self.merchant_name.as_str())),
+            ("02", self.merchant_city.as_deref()),
+        ]
+    }
+
+    /// Encode language template as EMV tag
+    pub fn encode(&self) -> Result<EMVTag> {
+        self.check()?;
+
+        let value = self
+            .sub_tags()
+            .into_iter()
+            .filter_map(|(id, value)| value.map(|value| EMVTag::new(id, value).encode()))
+            .collect::<String>();
+
+        Ok(EMVTag::new(tags::ALTERNATE_LANGUAGE, value))
+    }
+
+    /// Write the language template without allocating
+    ///
+    /// # Errors
+    /// Returns an error if the language code, name or city is invalid.
+    pub fn write_to(&self, writer: &mut PayloadWriter<'_>) -> Result<()> {
+        self.check()?;
+        writer.template(tags::ALTERNATE_LANGUAGE, &self.sub_tags())
+    }
+}
+
+/// Validity window of a dynamic QR code (template tag 90)
+///
+/// Times are Unix timestamps in seconds. The template carries
+/// [`constants::VALIDITY_TEMPLATE_ID`] in sub-tag 00, the optional start of
+/// the window in sub-tag 01 and the expiry in sub-tag 02. A code is valid from
+/// `not_before` up to, but not including, `expires_at`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Validity {
+    /// Earliest time the code may be paid (sub-tag 01)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub not_before: Option<u64>,
+    /// Time from which the code must be rejected (sub-tag 02)
+    pub expires_at: u64,
+}
+
+impl Validity {
+    /// Valid until `expires_at`
+    #[must_use]
+    pub fn until(expires_at: u64) -> Self {
+        Self {
+            not_before: None,
+            expires_at,
         }
-        if let Some(ref value) = self.additional_customer_data {
-            sub_tags.push(EMVTag::new("09", value));
+    }
+
+    /// Valid for `seconds` starting at `now`, e.g. a 10 minute checkout
+    #[must_use]
+    pub fn for_duration(now: u64, seconds: u64) -> Self {
+        Self {
+            not_before: Some(now),
+            expires_at: now.saturating_add(seconds),
         }
-        if let Some(ref value) = self.merchant_tax_id {
-            sub_tags.push(EMVTag::new("10", value));
+    }
+
+    /// Check the window against a clock value supplied by the caller
+    ///
+    /// # Errors
+    /// Returns [`QRError::NotYetValid`] before `not_before` and
+    /// [`QRError::Expired`] from `expires_at` onwards.
+    pub fn check(&self, now: u64) -> Result<()> {
+        if let Some(not_before) = self.not_before
+            && now < not_before
+        {
+            return Err(QRError::NotYetValid { not_before, now });
         }
-        if let Some(ref value) = self.merchant_channel {
-            sub_tags.push(EMVTag::new("11", value));
+        if now >= self.expires_at {
+            return Err(QRError::Expired {
+                expires_at: self.expires_at,
+                now,
+            });
         }
-        if let Some(ref value) = self.due_date {
-            sub_tags.push(EMVTag::new("50", value));
+        Ok(())
+    }
+
+    fn check_window(&self) -> Result<()> {
+        match self.not_before {
+            Some(not_before) if not_before >= self.expires_at => Err(QRError::InvalidValue {
+                field: "validity".to_string(),
+                value: format!("{not_before}..{}", self.expires_at),
+            }),
+            _ => Ok(()),
         }
-        if let Some(ref value) = self.amount_after_due_date {
-            sub_tags.push(EMVTag::new("51", value));
+    }
+
+    /// Encode the validity window as EMV tag
+    ///
+    /// # Errors
+    /// Returns an error if the window ends before it starts.
+    pub fn encode(&self) -> Result<EMVTag> {
+        self.check_window()?;
+
+        let mut value = EMVTag::new("00", constants::VALIDITY_TEMPLATE_ID).encode();
+        if let Some(not_before) = self.not_before {
+            value.push_str(&EMVTag::new("01", not_before.to_string()).encode());
         }
+        value.push_str(&EMVTag::new("02", self.expires_at.to_string()).encode());
 
-        if sub_tags.is_empty() {
-            None
-        } else {
-            let value = sub_tags
-                .iter()
-                .map(super::EMVTag::encode)
-                .collect::<String>();
-            Some(EMVTag::new(tags::ADDITIONAL_DATA, value))
+        Ok(EMVTag::new(tags::VALIDITY, value))
+    }
+
+    /// Write the validity window without allocating
+    ///
+    /// # Errors
+    /// Returns an error if the window ends before it starts.
+    pub fn write_to(&self, writer: &mut PayloadWriter<'_>) -> Result<()> {
+        self.check_window()?;
+
+        let mut start = [0; 20];
+        let mut end = [0; 20];
+        writer.template(
+            tags::VALIDITY,
+            &[
+                ("00", Some(constants::VALIDITY_TEMPLATE_ID)),
+                ("01", self.not_before.map(|t| decimal(t, &mut start))),
+                ("02", Some(decimal(self.expires_at, &mut end))),
+            ],
+        )
+    }
+}
+
+/// Format `n` in decimal into `buf`
+fn decimal(mut n: u64, buf: &mut [u8; 20]) -> &str {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        // n % 10 always fits in a u8
+        buf[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
         }
     }
+    // Only ASCII digits were written
+    core::str::from_utf8(&buf[start..]).unwrap_or_default()
 }
 
 /// Extension fields for tags 80-99
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
 pub struct ExtensionFields {
     /// Context/particulars of transaction (tag 80)
     pub transaction_context: Option<String>,
@@ -178,8 +419,54 @@ pub struct ExtensionFields {
     pub transaction_type_code: Option<String>,
 }
 
+/// Point of initiation method (tag 01)
+///
+/// Serialised as `"static"` or `"dynamic"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum PointOfInitiation {
+    /// Reusable code, e.g. printed at the till (`11`); may carry a fixed amount
+    Static,
+    /// Code for a single transaction (`12`); needs an amount or an
+    /// end-to-end ID to identify it
+    Dynamic,
+}
+
+impl PointOfInitiation {
+    /// Value of tag 01
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            PointOfInitiation::Static => constants::STATIC_QR_POI,
+            PointOfInitiation::Dynamic => constants::DYNAMIC_QR_POI,
+        }
+    }
+
+    /// Look up the method for a tag 01 value
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            constants::STATIC_QR_POI => Some(PointOfInitiation::Static),
+            constants::DYNAMIC_QR_POI => Some(PointOfInitiation::Dynamic),
+            _ => None,
+        }
+    }
+}
+
 /// Convenience fee configuration
-#[derive(Debug, Clone)]
+///
+/// Serialised as `{"type": "prompt"}`, `{"type": "fixed", "value": "5.00"}` or
+/// `{"type": "percentage", "value": "10"}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum ConvenienceFee {
     /// Prompt customer to add tip
     Prompt,
@@ -190,7 +477,16 @@ pub enum ConvenienceFee {
 }
 
 /// Payment scheme configuration
-#[derive(Debug, Clone)]
+///
+/// Serialised as an object tagged by `scheme` (`visa`, `mastercard`,
+/// `unionpay` or `ips_et`) alongside the variant's fields, e.g.
+/// `{"scheme": "visa", "account_info": "4111111111111111"}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "scheme", rename_all = "snake_case", deny_unknown_fields)
+)]
 pub enum SchemeConfig {
     Visa {
         account_info: String,
@@ -198,9 +494,11 @@ pub enum SchemeConfig {
     Mastercard {
         account_info: String,
     },
+    #[cfg_attr(feature = "serde", serde(rename = "unionpay"))]
     UnionPay {
         account_info: String,
     },
+    #[cfg_attr(feature = "serde", serde(rename = "ips_et"))]
     IPSET {
         guid: String,
         bic: String,
@@ -242,41 +540,47 @@ impl SchemeConfig {
         }
     }
 
+    fn check(&self) -> Result<()> {
+        if let SchemeConfig::IPSET { guid, bic, account } = self {
+            // Validate GUID format (UUID without hyphens)
+            if guid.len() != 32 || !guid.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(QRError::InvalidValue {
+                    field: "guid".to_string(),
+                    value: guid.clone(),
+                });
+            }
+
+            // Validate BIC format (8 or 11 characters)
+            if bic.len() != 8 && bic.len() != 11 {
+                return Err(QRError::InvalidValue {
+                    field: "bic".to_string(),
+                    value: bic.clone(),
+                });
+            }
+
+            // Validate account format
+            if account.len() > 24 {
+                return Err(QRError::InvalidValue {
+                    field: "account".to_string(),
+                    value: account.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Encode scheme as EMV tag
     pub fn encode(&self) -> Result<EMVTag> {
+        self.check()?;
+
         match self {
-            SchemeConfig::Visa { account_info } => Ok(EMVTag::new(tags::VISA, account_info)),
-            SchemeConfig::Mastercard { account_info } => {
-                Ok(EMVTag::new(tags::MASTERCARD, account_info))
-            }
-            SchemeConfig::UnionPay { account_info } => {
-                Ok(EMVTag::new(tags::UNIONPAY, account_info))
+            SchemeConfig::Visa { account_info }
+            | SchemeConfig::Mastercard { account_info }
+            | SchemeConfig::UnionPay { account_info } => {
+                Ok(EMVTag::new(self.tag_id(), account_info))
             }
             SchemeConfig::IPSET { guid, bic, account } => {
-                // Validate GUID format (UUID without hyphens)
-                if guid.len() != 32 || !guid.chars().all(|c| c.is_ascii_alphanumeric()) {
-                    return Err(QRError::InvalidValue {
-                        field: "guid".to_string(),
-                        value: guid.clone(),
-                    });
-                }
-
-                // Validate BIC format (8 or 11 characters)
-                if bic.len() != 8 && bic.len() != 11 {
-                    return Err(QRError::InvalidValue {
-                        field: "bic".to_string(),
-                        value: bic.clone(),
-                    });
-                }
-
-                // Validate account format
-                if account.len() > 24 {
-                    return Err(QRError::InvalidValue {
-                        field: "account".to_string(),
-                        value: account.clone(),
-                    });
-                }
-
                 // Build sub-tags
                 let sub_tag_00 = EMVTag::new("00", guid);
                 let sub_tag_01 = EMVTag::new("01", bic);
@@ -293,4 +597,22 @@ impl SchemeConfig {
             }
         }
     }
+
+    /// Write the scheme without allocating
+    ///
+    /// # Errors
+    /// Returns an error if the IPS ET GUID, BIC or account is invalid.
+    pub fn write_to(&self, writer: &mut PayloadWriter<'_>) -> Result<()> {
+        self.check()?;
+
+        match self {
+            SchemeConfig::Visa { account_info }
+            | SchemeConfig::Mastercard { account_info }
+            | SchemeConfig::UnionPay { account_info } => writer.tag(self.tag_id(), account_info),
+            SchemeConfig::IPSET { guid, bic, account } => writer.template(
+                tags::IPS_ET,
+                &[("00", Some(guid)), ("01", Some(bic)), ("02", Some(account))],
+            ),
+        }
+    }
 }
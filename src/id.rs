@@ -0,0 +1,353 @@
+//! Reference and end-to-end ID generation for dynamic QR codes
+//!
+//! Every [`IdGenerator`] produces IDs of a fixed length, so it is known up
+//! front whether they fit the reference label (tag 62 sub-tag 05, at most
+//! [`constants::MAX_REFERENCE_LABEL_LEN`] characters) or the end-to-end ID
+//! (tag 84, at most [`constants::MAX_END_TO_END_ID_LEN`] characters):
+//!
+//! | Generator | Length | Reference label | End-to-end ID |
+//! |-----------|--------|-----------------|---------------|
+//! | [`UlidGenerator`] | 26 | no | yes |
+//! | [`CounterGenerator`] | prefix + 12 | prefix up to 13 | prefix up to 23 |
+//! | [`UuidGenerator`] | 32 | no | yes |
+//!
+//! The clock and random source are injected, so tests can produce the same
+//! IDs on every run:
+//!
+//! ```
+//! use ethqr_gen::id::{CounterGenerator, IdGenerator};
+//!
+//! let mut ids = CounterGenerator::new("T7-", || 1_760_000_000_000);
+//! assert_eq!(ids.generate(), "T7-1MEEY0000000");
+//! assert_eq!(ids.generate(), "T7-1MEEY0000001");
+//! assert_eq!(ids.id_len(), 15);
+//! ```
+
+use alloc::string::String;
+
+use crate::constants;
+
+/// Crockford base32, which avoids the easily confused I, L, O and U
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Source of the current time in milliseconds since the Unix epoch
+///
+/// Implemented for closures `Fn() -> u64`.
+pub trait Clock {
+    fn now_millis(&self) -> u64;
+}
+
+impl<F: Fn() -> u64> Clock for F {
+    fn now_millis(&self) -> u64 {
+        self()
+    }
+}
+
+/// The system clock (requires `std` feature)
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| {
+                u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+            })
+    }
+}
+
+/// Source of random bits
+///
+/// Implemented for closures `FnMut() -> u64`. IDs only need to be unlikely
+/// to collide, not unpredictable, so any decent generator will do.
+pub trait RandomSource {
+    fn next_u64(&mut self) -> u64;
+}
+
+impl<F: FnMut() -> u64> RandomSource for F {
+    fn next_u64(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// Random source seeded per process from the standard library's hasher keys
+/// (requires `std` feature)
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct SystemRandom {
+    state: std::hash::RandomState,
+    counter: u64,
+}
+
+#[cfg(feature = "std")]
+impl SystemRandom {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: std::hash::RandomState::new(),
+            counter: 0,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for SystemRandom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl RandomSource for SystemRandom {
+    fn next_u64(&mut self) -> u64 {
+        use core::hash::BuildHasher;
+
+        self.counter = self.counter.wrapping_add(1);
+        self.state.hash_one(self.counter)
+    }
+}
+
+/// Generates IDs of a fixed length
+pub trait IdGenerator {
+    /// Length of every ID this generator produces
+    fn id_len(&self) -> usize;
+
+    /// Produce the next ID
+    fn generate(&mut self) -> String;
+
+    /// Whether the IDs fit the reference label (tag 62 sub-tag 05)
+    fn fits_reference_label(&self) -> bool {
+        self.id_len() <= constants::MAX_REFERENCE_LABEL_LEN
+    }
+
+    /// Whether the IDs fit the end-to-end ID (tag 84)
+    fn fits_end_to_end_id(&self) -> bool {
+        self.id_len() <= constants::MAX_END_TO_END_ID_LEN
+    }
+}
+
+/// Append the low `5 * len` bits of `value` in Crockford base32
+fn encode_base32(value: u128, out: &mut String, len: usize) {
+    for digit in (0..len).rev() {
+        let index = (value >> (5 * digit)) & 0x1F;
+        out.push(char::from(CROCKFORD[index as usize]));
+    }
+}
+
+/// [ULID](https://github.com/ulid/spec) generator: a 48-bit millisecond
+/// timestamp and 80 random bits in 26 Crockford base32 characters
+///
+/// IDs sort by creation time. While the clock has not moved past the last ID
+/// the random part is incremented rather than redrawn, carrying into the
+/// timestamp if it overflows, so IDs from one generator always increase.
+#[derive(Debug, Clone)]
+pub struct UlidGenerator<C, R> {
+    clock: C,
+    rng: R,
+    last: Option<(u64, u128)>,
+}
+
+/// Mask of the 48 timestamp bits of a ULID
+const ULID_TIME_MASK: u64 = (1 << 48) - 1;
+
+/// Mask of the 80 random bits of a ULID
+const ULID_RANDOM_MASK: u128 = (1 << 80) - 1;
+
+impl<C: Clock, R: RandomSource> UlidGenerator<C, R> {
+    #[must_use]
+    pub fn new(clock: C, rng: R) -> Self {
+        Self {
+            clock,
+            rng,
+            last: None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl UlidGenerator<SystemClock, SystemRandom> {
+    /// Generator using the system clock and [`SystemRandom`]
+    #[must_use]
+    pub fn system() -> Self {
+        Self::new(SystemClock, SystemRandom::new())
+    }
+}
+
+impl<C: Clock, R: RandomSource> IdGenerator for UlidGenerator<C, R> {
+    fn id_len(&self) -> usize {
+        26
+    }
+
+    fn generate(&mut self) -> String {
+        let now = self.clock.now_millis() & ULID_TIME_MASK;
+        let (millis, random) = match self.last {
+            Some((last_millis, ULID_RANDOM_MASK)) if now <= last_millis => {
+                ((last_millis + 1) & ULID_TIME_MASK, 0)
+            }
+            Some((last_millis, last_random)) if now <= last_millis => {
+                (last_millis, last_random + 1)
+            }
+            _ => {
+                let high = u128::from(self.rng.next_u64() & 0xFFFF);
+                (now, (high << 64) | u128::from(self.rng.next_u64()))
+            }
+        };
+        self.last = Some((millis, random));
+
+        let mut id = String::with_capacity(26);
+        encode_base32((u128::from(millis) << 80) | random, &mut id, 26);
+        id
+    }
+}
+
+/// Time and counter generator: a fixed prefix, the Unix time in seconds (7
+/// Crockford base32 characters) and a 5-character counter
+///
+/// Fully deterministic for a given clock, which makes IDs easy to trace back
+/// to a till. Give each device its own prefix; one generator produces over 33
+/// million IDs per second before the counter wraps.
+#[derive(Debug, Clone)]
+pub struct CounterGenerator<C> {
+    prefix: String,
+    clock: C,
+    counter: u32,
+}
+
+/// Number of distinct counter values
+const COUNTER_RANGE: u32 = 1 << 25;
+
+impl<C: Clock> CounterGenerator<C> {
+    #[must_use]
+    pub fn new(prefix: impl Into<String>, clock: C) -> Self {
+        Self {
+            prefix: prefix.into(),
+            clock,
+            counter: 0,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl CounterGenerator<SystemClock> {
+    /// Generator using the system clock
+    #[must_use]
+    pub fn system(prefix: impl Into<String>) -> Self {
+        Self::new(prefix, SystemClock)
+    }
+}
+
+impl<C: Clock> IdGenerator for CounterGenerator<C> {
+    fn id_len(&self) -> usize {
+        self.prefix.len() + 12
+    }
+
+    fn generate(&mut self) -> String {
+        let seconds = self.clock.now_millis() / 1000;
+        let counter = self.counter;
+        self.counter = (self.counter + 1) % COUNTER_RANGE;
+
+        let mut id = String::with_capacity(self.id_len());
+        id.push_str(&self.prefix);
+        encode_base32(u128::from(seconds), &mut id, 7);
+        encode_base32(u128::from(counter), &mut id, 5);
+        id
+    }
+}
+
+/// Random (version 4) UUID generator, written as 32 lowercase hex digits
+/// without hyphens
+#[derive(Debug, Clone)]
+pub struct UuidGenerator<R> {
+    rng: R,
+}
+
+impl<R: RandomSource> UuidGenerator<R> {
+    #[must_use]
+    pub fn new(rng: R) -> Self {
+        Self { rng }
+    }
+}
+
+#[cfg(feature = "std")]
+impl UuidGenerator<SystemRandom> {
+    /// Generator using [`SystemRandom`]
+    #[must_use]
+    pub fn system() -> Self {
+        Self::new(SystemRandom::new())
+    }
+}
+
+impl<R: RandomSource> IdGenerator for UuidGenerator<R> {
+    fn id_len(&self) -> usize {
+        32
+    }
+
+    fn generate(&mut self) -> String {
+        use core::fmt::Write;
+
+        let high = (self.rng.next_u64() & !0xF000) | 0x4000;
+        let low = (self.rng.next_u64() & !(0b11 << 62)) | (0b10 << 62);
+
+        let mut id = String::with_capacity(32);
+        // Writing to a String cannot fail
+        let _ = write!(id, "{high:016x}{low:016x}");
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence() -> impl FnMut() -> u64 {
+        let mut next = 0u64;
+        move || {
+            next += 1;
+            next.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        }
+    }
+
+    #[test]
+    fn test_ulid_layout_and_monotonicity() {
+        let mut ids = UlidGenerator::new(|| 1_469_918_176_385, || u64::MAX);
+        let first = ids.generate();
+        let second = ids.generate();
+        let third = ids.generate();
+
+        assert_eq!(&first[..10], "01ARYZ6S41");
+        assert_eq!(&first[10..], "ZZZZZZZZZZZZZZZZ");
+        // Incrementing past the largest value carries into the timestamp
+        assert_eq!(second, "01ARYZ6S420000000000000000");
+        assert_eq!(third, "01ARYZ6S420000000000000001");
+        assert!(first < second && second < third);
+        assert_eq!(first.len(), ids.id_len());
+        assert!(!ids.fits_reference_label());
+        assert!(ids.fits_end_to_end_id());
+    }
+
+    #[test]
+    fn test_counter_is_deterministic() {
+        let mut ids = CounterGenerator::new("T7-", || 1_760_000_000_999);
+        let generated: alloc::vec::Vec<_> = (0..3).map(|_| ids.generate()).collect();
+        assert_eq!(
+            generated,
+            ["T7-1MEEY0000000", "T7-1MEEY0000001", "T7-1MEEY0000002"]
+        );
+        assert!(ids.fits_reference_label());
+        assert!(!CounterGenerator::new("x".repeat(14), || 0).fits_reference_label());
+    }
+
+    #[test]
+    fn test_uuid_version_and_variant() {
+        let mut ids = UuidGenerator::new(sequence());
+        let first = ids.generate();
+        assert_eq!(first.len(), 32);
+        assert_eq!(&first[12..13], "4");
+        assert!(matches!(&first[16..17], "8" | "9" | "a" | "b"));
+        assert_ne!(first, ids.generate());
+        assert!(!ids.fits_reference_label() && ids.fits_end_to_end_id());
+    }
+}
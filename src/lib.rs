@@ -8,7 +8,22 @@
 //! - `EMVCo` QR Code standard compliance
 //! - Support for multiple payment schemes (Visa, Mastercard, IPS ET, etc.)
 //! - Static and dynamic QR code generation
+//! - Payload parsing and validation reports
 //! - QR code image generation (with `qr-image` feature)
+//! - Coloured and styled QR code images with contrast checks (with `qr-image` feature)
+//! - In-memory PNG/JPEG/WebP encoding (with `qr-image` feature)
+//! - SVG and PNG data URIs for embedding in HTML and email
+//! - Batch generation from merchant CSV files (with `batch` feature)
+//! - Merchant profile files in TOML, YAML or JSON (with `profile` feature)
+//! - `Serialize`/`Deserialize` for the data model (with `serde` feature)
+//! - Local HTTP service for generation, decoding and validation (with `server` feature)
+//! - Terminal rendering for CLI and SSH sessions
+//! - ESC/POS output for thermal receipt printers
+//! - `no_std` + `alloc` payload construction, CRC and parsing (disable the default `std` feature)
+//! - Allocation-free encoding into caller buffers ([`QRBuilder::encode_into`], `heapless` feature)
+//! - Dynamic QR codes stamped from a pre-encoded merchant template ([`template`])
+//! - Reference label and end-to-end ID generators ([`id`])
+//! - HMAC-SHA256 and Ed25519 signed payloads with verification (with `signing` feature)
 //!
 //! ## Quick Start
 //!
@@ -73,6 +88,29 @@
 //! # }
 //! ```
 //!
+//! ### Serialisation (requires `serde` feature)
+//!
+//! [`QRBuilder`], [`EMVTag`], [`parser::ParsedQR`] and the types in [`fields`]
+//! use their Rust field names in snake case. Schemes are tagged by `scheme`:
+//!
+//! ```json
+//! {
+//!   "payload_format_indicator": "01",
+//!   "point_of_initiation": null,
+//!   "merchant_name": "Coffee Shop",
+//!   "merchant_city": "Addis Ababa",
+//!   "merchant_category_code": "5812",
+//!   "schemes": [{ "scheme": "visa", "account_info": "4111111111111111" }],
+//!   "transaction_amount": "25.00",
+//!   "transaction_currency": "230",
+//!   "additional_data": { "bill_number": "INV-001" },
+//!   "language_template": null,
+//!   "transaction_context": null,
+//!   "end_to_end_id": null,
+//!   "validity": null
+//! }
+//! ```
+//!
 //! ## Payment Schemes
 //!
 //! The library supports multiple payment schemes through the [`SchemeConfig`] type:
@@ -82,28 +120,71 @@
 //! - **Unionpay**: `SchemeConfig::unionpay("account_info")`
 //! - **IPS ET**: `SchemeConfig::ips_et("guid", "bic", "account_info")` (Ethiopian Interbank Payment System)
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "batch")]
+pub mod batch;
 pub mod crc;
+pub mod data_uri;
+pub mod encoder;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod escpos;
+#[cfg(feature = "qr-image")]
+pub mod export;
 pub mod fields;
-
-use std::fmt::{self, Write};
-
+pub mod id;
+pub mod parser;
+#[cfg(feature = "profile")]
+pub mod profile;
+pub mod repair;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "signing")]
+pub mod signing;
+#[cfg(feature = "qr-image")]
+pub mod style;
+pub mod template;
+#[cfg(feature = "std")]
+pub mod terminal;
+pub mod validation;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::encoder::PayloadWriter;
 use crate::error::{QRError, Result};
-use crate::fields::{AdditionalData, SchemeConfig};
+use crate::fields::{AdditionalData, LanguageTemplate, PointOfInitiation, SchemeConfig, Validity};
+use crate::id::IdGenerator;
 
 #[cfg(feature = "qr-image")]
 use image::{DynamicImage, ImageBuffer, Luma};
+#[cfg(feature = "std")]
 use qrcode::QrCode;
+#[cfg(feature = "std")]
+use qrcode::render::svg;
 
 pub mod constants {
     pub const PAYLOAD_FORMAT_INDICATOR: &str = "01";
     pub const ETB_CURRENCY_CODE: &str = "230";
     pub const ETHIOPIA_COUNTRY_CODE: &str = "ET";
     pub const MAX_QR_LENGTH: usize = 512;
+    /// Largest value a two-digit TLV length can describe
+    pub const MAX_VALUE_LEN: usize = 99;
     pub const STATIC_QR_POI: &str = "11";
     pub const DYNAMIC_QR_POI: &str = "12";
     pub const MAX_MERCHANT_NAME_LEN: usize = 25;
     pub const MAX_MERCHANT_CITY_LEN: usize = 15;
+    pub const MAX_REFERENCE_LABEL_LEN: usize = 25;
+    /// ISO 20022 `EndToEndId` limit
+    pub const MAX_END_TO_END_ID_LEN: usize = 35;
+    /// Globally unique identifier in sub-tag 00 of the validity template
+    pub const VALIDITY_TEMPLATE_ID: &str = "ET.ETHQR.VALIDITY";
+    pub const SIGNATURE_TEMPLATE_ID: &str = "ET.ETHQR.SIG";
 
     pub const DEFAULT_QRIMAGE_SIZE: u32 = 10;
 }
@@ -121,6 +202,10 @@ pub mod tags {
     pub const CRC: &str = "63";
     pub const ALTERNATE_LANGUAGE: &str = "64";
     pub const TRANSACTION_CONTEXT: &str = "80";
+    pub const END_TO_END_ID: &str = "84";
+    pub const VALIDITY: &str = "90";
+    pub const SIGNATURE: &str = "91";
+    pub const SIGNATURE_VALUE: &str = "92";
 
     // Scheme allocations
     pub const VISA: &str = "02";
@@ -131,6 +216,7 @@ pub mod tags {
 
 /// Represents an EMV tag with ID, length, and value
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EMVTag {
     pub id: String,
     pub value: String,
@@ -156,9 +242,19 @@ impl EMVTag {
 }
 
 /// Builder for constructing QR codes
-#[derive(Default, Clone)]
+///
+/// With the `serde` feature the builder serialises its fields by name;
+/// missing fields take the values set by [`QRBuilder::new`]. Deserialised
+/// values are validated like any other when the payload is built.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default = "QRBuilder::new", deny_unknown_fields)
+)]
 pub struct QRBuilder {
     payload_format_indicator: String,
+    point_of_initiation: Option<PointOfInitiation>,
     merchant_name: String,
     merchant_city: String,
     merchant_category_code: String,
@@ -166,7 +262,10 @@ pub struct QRBuilder {
     transaction_amount: Option<String>,
     transaction_currency: String,
     additional_data: Option<AdditionalData>,
+    language_template: Option<LanguageTemplate>,
     transaction_context: Option<String>,
+    end_to_end_id: Option<String>,
+    validity: Option<Validity>,
 }
 
 impl QRBuilder {
@@ -203,6 +302,34 @@ impl QRBuilder {
         self
     }
 
+    /// Set the point of initiation explicitly
+    ///
+    /// By default a code is dynamic if it has a transaction amount and static
+    /// otherwise. Set it to issue static codes with a fixed amount, or dynamic
+    /// request-to-pay codes identified by an end-to-end ID instead of an
+    /// amount.
+    ///
+    /// ```
+    /// use ethqr_gen::{QRBuilder, fields::{PointOfInitiation, SchemeConfig}, parser};
+    ///
+    /// let payload = QRBuilder::new()
+    ///     .merchant_name("Coffee Shop")
+    ///     .merchant_city("Addis Ababa")
+    ///     .merchant_category_code("5812")
+    ///     .add_scheme(SchemeConfig::visa("4111111111111111"))
+    ///     .point_of_initiation(PointOfInitiation::Dynamic)
+    ///     .end_to_end_id("RTP-2024-0001")
+    ///     .build_payload()?;
+    ///
+    /// let parsed = parser::parse(&payload)?;
+    /// assert!(parsed.is_dynamic() && parsed.transaction_amount.is_none());
+    /// # Ok::<(), ethqr_gen::error::QRError>(())
+    /// ```
+    pub fn point_of_initiation(mut self, point_of_initiation: PointOfInitiation) -> Self {
+        self.point_of_initiation = Some(point_of_initiation);
+        self
+    }
+
     /// Set transaction amount (for dynamic QR)
     pub fn transaction_amount(mut self, amount: impl Into<String>) -> Self {
         self.transaction_amount = Some(amount.into());
@@ -215,13 +342,121 @@ impl QRBuilder {
         self
     }
 
+    /// Set merchant information in an alternate language
+    pub fn language_template(mut self, template: LanguageTemplate) -> Self {
+        self.language_template = Some(template);
+        self
+    }
+
     /// Set transaction context
     pub fn transaction_context(mut self, context: impl Into<String>) -> Self {
         self.transaction_context = Some(context.into());
         self
     }
 
+    /// Set the end-to-end ID for request-to-pay (tag 84)
+    pub fn end_to_end_id(mut self, id: impl Into<String>) -> Self {
+        self.end_to_end_id = Some(id.into());
+        self
+    }
+
+    /// Set the reference label (tag 62 sub-tag 05) to a fresh ID from
+    /// `generator`, keeping any other additional data
+    ///
+    /// ```
+    /// use ethqr_gen::{QRBuilder, fields::SchemeConfig, id::CounterGenerator};
+    ///
+    /// let merchant = QRBuilder::new()
+    ///     .merchant_name("Coffee Shop")
+    ///     .merchant_city("Addis Ababa")
+    ///     .merchant_category_code("5812")
+    ///     .add_scheme(SchemeConfig::visa("4111111111111111"));
+    ///
+    /// let mut ids = CounterGenerator::new("T7-", || 1_760_000_000_000);
+    /// let first = merchant.clone().transaction_amount("10.00").stamp_reference_label(&mut ids);
+    /// let second = merchant.transaction_amount("10.00").stamp_reference_label(&mut ids);
+    /// assert_ne!(first.build_payload()?, second.build_payload()?);
+    /// # Ok::<(), ethqr_gen::error::QRError>(())
+    /// ```
+    pub fn stamp_reference_label(mut self, generator: &mut impl IdGenerator) -> Self {
+        let data = self.additional_data.take().unwrap_or_default();
+        self.additional_data = Some(data.reference_label(generator.generate()));
+        self
+    }
+
+    /// Set the end-to-end ID (tag 84) to a fresh ID from `generator`
+    pub fn stamp_end_to_end_id(self, generator: &mut impl IdGenerator) -> Self {
+        self.end_to_end_id(generator.generate())
+    }
+
+    /// Set the window in which the code may be paid (template tag 90)
+    ///
+    /// Only dynamic codes, which carry an amount, may have a window.
+    pub fn validity(mut self, validity: Validity) -> Self {
+        self.validity = Some(validity);
+        self
+    }
+
     fn validate(&self) -> Result<()> {
+        self.validate_fields()?;
+        self.validate_mode(self.transaction_amount.is_some())
+    }
+
+    /// Point of initiation to encode: the explicit one, or inferred from
+    /// whether there is an amount
+    fn resolve_point_of_initiation(&self, has_amount: bool) -> PointOfInitiation {
+        self.point_of_initiation.unwrap_or(if has_amount {
+            PointOfInitiation::Dynamic
+        } else {
+            PointOfInitiation::Static
+        })
+    }
+
+    /// Check the rules of the point of initiation
+    fn validate_mode(&self, has_amount: bool) -> Result<()> {
+        match self.resolve_point_of_initiation(has_amount) {
+            // Static codes are printed once and reused, so they cannot expire
+            PointOfInitiation::Static if self.validity.is_some() => Err(QRError::ValidationError {
+                message: "static QR code cannot carry a validity window".to_string(),
+            }),
+            PointOfInitiation::Static if self.end_to_end_id.is_some() => {
+                Err(QRError::ValidationError {
+                    message: "static QR code cannot carry an end-to-end ID".to_string(),
+                })
+            }
+            PointOfInitiation::Dynamic if !has_amount && self.end_to_end_id.is_none() => {
+                Err(QRError::ValidationError {
+                    message: "dynamic QR code needs a transaction amount or an end-to-end ID"
+                        .to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the fields shared by static and dynamic codes
+    fn validate_fields(&self) -> Result<()> {
+        // Only reachable through deserialisation, which has no setter checks
+        if self.payload_format_indicator != constants::PAYLOAD_FORMAT_INDICATOR {
+            return Err(QRError::InvalidValue {
+                field: "payload_format_indicator".to_string(),
+                value: self.payload_format_indicator.clone(),
+            });
+        }
+
+        // ISO 4217 numeric code
+        if self.transaction_currency.len() != 3
+            || !self
+                .transaction_currency
+                .chars()
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(QRError::InvalidValue {
+                field: "currency".to_string(),
+                value: self.transaction_currency.clone(),
+            });
+        }
+
         // Validate merchant information
         if self.merchant_name.len() > constants::MAX_MERCHANT_NAME_LEN {
             return Err(QRError::ValueTooLong {
@@ -258,105 +493,229 @@ impl QRBuilder {
             });
         }
 
+        if let Some(label) = self
+            .additional_data
+            .as_ref()
+            .and_then(|data| data.reference_label.as_ref())
+            && label.len() > constants::MAX_REFERENCE_LABEL_LEN
+        {
+            return Err(QRError::ValueTooLong {
+                field: "reference_label".to_string(),
+                length: label.len(),
+                max_length: constants::MAX_REFERENCE_LABEL_LEN,
+            });
+        }
+
+        if let Some(ref id) = self.end_to_end_id
+            && id.len() > constants::MAX_END_TO_END_ID_LEN
+        {
+            return Err(QRError::ValueTooLong {
+                field: "end_to_end_id".to_string(),
+                length: id.len(),
+                max_length: constants::MAX_END_TO_END_ID_LEN,
+            });
+        }
+
         Ok(())
     }
 
-    fn build_payload(&self) -> Result<String> {
+    /// Build the encoded payload string, including the trailing CRC
+    ///
+    /// # Errors
+    /// Returns an error if the builder fails validation or the payload exceeds
+    /// the maximum QR length.
+    pub fn build_payload(&self) -> Result<String> {
+        let mut buf = [0u8; constants::MAX_QR_LENGTH];
+        Ok(self.encode_into(&mut buf)?.to_string())
+    }
+
+    /// Encode the payload into `buf` without allocating, returning the
+    /// written prefix of `buf`
+    ///
+    /// A buffer of [`constants::MAX_QR_LENGTH`] bytes always suffices.
+    ///
+    /// ```
+    /// use ethqr_gen::{QRBuilder, constants};
+    /// use ethqr_gen::fields::SchemeConfig;
+    ///
+    /// let builder = QRBuilder::new()
+    ///     .merchant_name("Coffee Shop")
+    ///     .merchant_city("Addis Ababa")
+    ///     .merchant_category_code("5812")
+    ///     .add_scheme(SchemeConfig::visa("4111111111111111"));
+    ///
+    /// let mut buf = [0u8; constants::MAX_QR_LENGTH];
+    /// let payload = builder.encode_into(&mut buf)?;
+    /// assert_eq!(payload, builder.build_payload()?);
+    /// # Ok::<(), ethqr_gen::error::QRError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the builder fails validation, the payload exceeds
+    /// the maximum QR length or it does not fit in `buf`.
+    pub fn encode_into<'a>(&self, buf: &'a mut [u8]) -> Result<&'a str> {
         self.validate()?;
 
-        let point_of_initiation = if self.transaction_amount.is_some() {
-            Some(constants::DYNAMIC_QR_POI.to_string())
-        } else {
-            Some(constants::STATIC_QR_POI.to_string())
-        };
+        let mut writer = PayloadWriter::new(buf);
+        self.write_fields(&mut writer)?;
+
+        // Append CRC and validate length
+        writer.finish()
+    }
 
-        let mut tags = Vec::new();
+    /// Write every field except the CRC
+    fn write_fields(&self, writer: &mut PayloadWriter<'_>) -> Result<()> {
+        let point_of_initiation =
+            self.resolve_point_of_initiation(self.transaction_amount.is_some());
+
+        self.write_head(writer, point_of_initiation)?;
+
+        // Transaction Amount (optional)
+        if let Some(ref amount) = self.transaction_amount {
+            writer.tag(tags::TRANSACTION_AMOUNT, amount)?;
+        }
 
+        self.write_merchant(writer)?;
+
+        // Additional Data (optional)
+        if let Some(ref additional_data) = self.additional_data {
+            additional_data.write_to(writer)?;
+        }
+
+        self.write_tail(writer)
+    }
+
+    /// Write the fields before the transaction amount
+    pub(crate) fn write_head(
+        &self,
+        writer: &mut PayloadWriter<'_>,
+        point_of_initiation: PointOfInitiation,
+    ) -> Result<()> {
         // Payload Format Indicator (mandatory)
-        tags.push(EMVTag::new(
+        writer.tag(
             tags::PAYLOAD_FORMAT_INDICATOR,
             &self.payload_format_indicator,
-        ));
+        )?;
 
-        // Point of Initiation (optional)
-        if let Some(ref poi) = point_of_initiation {
-            tags.push(EMVTag::new(tags::POINT_OF_INITIATION, poi));
-        }
+        // Point of Initiation
+        writer.tag(tags::POINT_OF_INITIATION, point_of_initiation.code())?;
 
         // Merchant Account Information (schemes)
         for scheme in &self.schemes {
-            tags.push(scheme.encode()?);
+            scheme.write_to(writer)?;
         }
 
         // Merchant Category Code (mandatory)
-        tags.push(EMVTag::new(
-            tags::MERCHANT_CATEGORY_CODE,
-            &self.merchant_category_code,
-        ));
+        writer.tag(tags::MERCHANT_CATEGORY_CODE, &self.merchant_category_code)?;
 
         // Transaction Currency (mandatory)
-        tags.push(EMVTag::new(
-            tags::TRANSACTION_CURRENCY,
-            &self.transaction_currency,
-        ));
-
-        // Transaction Amount (optional)
-        if let Some(ref amount) = self.transaction_amount {
-            tags.push(EMVTag::new(tags::TRANSACTION_AMOUNT, amount));
-        }
+        writer.tag(tags::TRANSACTION_CURRENCY, &self.transaction_currency)
+    }
 
+    /// Write the fields between the transaction amount and additional data
+    pub(crate) fn write_merchant(&self, writer: &mut PayloadWriter<'_>) -> Result<()> {
         // Country Code (mandatory)
-        tags.push(EMVTag::new(
-            tags::COUNTRY_CODE,
-            constants::ETHIOPIA_COUNTRY_CODE,
-        ));
+        writer.tag(tags::COUNTRY_CODE, constants::ETHIOPIA_COUNTRY_CODE)?;
 
         // Merchant Name (mandatory)
-        tags.push(EMVTag::new(tags::MERCHANT_NAME, &self.merchant_name));
+        writer.tag(tags::MERCHANT_NAME, &self.merchant_name)?;
 
         // Merchant City (mandatory)
-        tags.push(EMVTag::new(tags::MERCHANT_CITY, &self.merchant_city));
+        writer.tag(tags::MERCHANT_CITY, &self.merchant_city)
+    }
 
-        // Additional Data (optional)
-        if let Some(ref additional_data) = self.additional_data
-            && let Some(tag) = additional_data.encode()
-        {
-            tags.push(tag);
+    /// Write the fields after additional data
+    pub(crate) fn write_tail(&self, writer: &mut PayloadWriter<'_>) -> Result<()> {
+        // Merchant Information - Language Template (optional)
+        if let Some(ref template) = self.language_template {
+            template.write_to(writer)?;
         }
 
         // Transaction Context (optional)
         if let Some(ref context) = self.transaction_context {
-            tags.push(EMVTag::new(tags::TRANSACTION_CONTEXT, context));
+            writer.tag(tags::TRANSACTION_CONTEXT, context)?;
         }
 
-        // Build payload without CRC
-        let mut payload = tags.iter().map(EMVTag::encode).collect::<String>();
-
-        // Calculate and append CRC
-        let crc = crc::calculate_crc16(&format!("{payload}6304"));
-        write!(&mut payload, "63{:02}{}", crc.len(), crc).map_err(|e| QRError::BuilderError {
-            message: format!("Failed to build QR code: {e}"),
-        })?;
+        // End-to-End ID (optional)
+        if let Some(ref id) = self.end_to_end_id {
+            writer.tag(tags::END_TO_END_ID, id)?;
+        }
 
-        // Validate length
-        if payload.len() > constants::MAX_QR_LENGTH {
-            return Err(QRError::PayloadTooLong {
-                length: payload.len(),
-            });
+        // Validity Window (optional)
+        if let Some(ref validity) = self.validity {
+            validity.write_to(writer)?;
         }
 
-        Ok(payload)
+        Ok(())
+    }
+
+    /// Encode the payload into a fixed-capacity `heapless` string
+    ///
+    /// # Errors
+    /// Returns an error if the builder fails validation or the payload exceeds
+    /// the maximum QR length.
+    #[cfg(feature = "heapless")]
+    pub fn encode_heapless(&self) -> Result<heapless::String<{ constants::MAX_QR_LENGTH }>> {
+        let mut buf = [0u8; constants::MAX_QR_LENGTH];
+        let payload = self.encode_into(&mut buf)?;
+        heapless::String::try_from(payload).map_err(|_| QRError::PayloadTooLong {
+            length: payload.len(),
+        })
+    }
+
+    /// Build the payload with a signature over its fields (requires `signing` feature)
+    ///
+    /// See [`signing`] for the templates appended and how to verify them.
+    ///
+    /// # Errors
+    /// Returns an error if the builder fails validation, the signer fails or
+    /// the signed payload exceeds the maximum QR length.
+    #[cfg(feature = "signing")]
+    pub fn build_signed_payload(&self, signer: &impl signing::Signer) -> Result<String> {
+        self.validate()?;
+
+        let mut buf = [0u8; constants::MAX_QR_LENGTH];
+        let mut writer = PayloadWriter::new(&mut buf);
+        self.write_fields(&mut writer)?;
+        signing::write_signature(&mut writer, signer)?;
+
+        Ok(writer.finish()?.to_string())
     }
 
     /// Build the QR code and return a QR code object
     ///
     /// # Errors
+    #[cfg(feature = "std")]
     pub fn build(&self) -> Result<QrCode> {
         let payload = self.build_payload()?;
 
         Ok(QrCode::new(&payload)?)
     }
 
+    /// Build QR code as SVG markup at least `size` pixels wide
+    ///
+    /// # Errors
+    /// Returns an error if the payload is invalid.
+    #[cfg(feature = "std")]
+    pub fn build_svg(&self, size: u32) -> Result<String> {
+        let svg = self
+            .build()?
+            .render::<svg::Color>()
+            .min_dimensions(size, size)
+            .build();
+
+        Ok(svg)
+    }
+
+    /// Build QR code as a `data:image/svg+xml` URI at least `size` pixels wide
+    ///
+    /// # Errors
+    /// Returns an error if the payload is invalid.
+    #[cfg(feature = "std")]
+    pub fn build_svg_data_uri(&self, size: u32) -> Result<String> {
+        Ok(data_uri::svg_data_uri(&self.build_svg(size)?))
+    }
+
     /// Build QR code as an image
     #[cfg(feature = "qr-image")]
     pub fn build_image(&mut self) -> Result<DynamicImage> {
@@ -413,6 +772,56 @@ impl QRBuilder {
 
         Ok(image)
     }
+
+    /// Build QR code as a styled RGBA image
+    ///
+    /// # Errors
+    /// Returns an error if the payload is invalid or the style colours do not
+    /// provide enough contrast to be scanned.
+    #[cfg(feature = "qr-image")]
+    pub fn build_styled_image(&self, style: &style::QRStyle) -> Result<DynamicImage> {
+        let qr_code = self.build()?;
+
+        Ok(DynamicImage::ImageRgba8(style.render(&qr_code)?))
+    }
+
+    /// Build QR code as encoded image bytes
+    ///
+    /// # Errors
+    /// Returns an error if the payload is invalid or the image cannot be
+    /// encoded in the requested format.
+    #[cfg(feature = "qr-image")]
+    pub fn build_image_bytes(&self, encoder: &export::ImageEncoder) -> Result<Vec<u8>> {
+        let image = self
+            .build()?
+            .render::<Luma<u8>>()
+            .module_dimensions(
+                constants::DEFAULT_QRIMAGE_SIZE,
+                constants::DEFAULT_QRIMAGE_SIZE,
+            )
+            .build();
+
+        encoder.encode(&DynamicImage::ImageLuma8(image))
+    }
+
+    /// Build QR code as a `data:image/png;base64` URI at least `size` pixels wide
+    ///
+    /// # Errors
+    /// Returns an error if the payload is invalid or the image cannot be
+    /// encoded.
+    #[cfg(feature = "qr-image")]
+    pub fn build_png_data_uri(&self, size: u32) -> Result<String> {
+        let image = self
+            .build()?
+            .render::<Luma<u8>>()
+            .min_dimensions(size, size)
+            .build();
+
+        let format = export::ImageFormat::Png;
+        let bytes = export::ImageEncoder::new(format).encode(&DynamicImage::ImageLuma8(image))?;
+
+        Ok(data_uri::base64_data_uri(format.mime_type(), &bytes))
+    }
 }
 
 impl fmt::Display for QRBuilder {
@@ -423,3 +832,103 @@ impl fmt::Display for QRBuilder {
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::fields::{ConvenienceFee, ExtensionFields};
+    use serde_json::json;
+
+    #[test]
+    fn test_builder_serde_round_trip() {
+        let builder = QRBuilder::new()
+            .merchant_name("Restaurant")
+            .merchant_city("Dire Dawa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::ips_et(
+                "581b314e257f41bfbbdc6384daa31d16",
+                "CBETETAA",
+                "10000171234567890",
+            ))
+            .transaction_amount("50.00")
+            .additional_data(AdditionalData::new().bill_number("INV-001"))
+            .language_template(LanguageTemplate::new("am", "ምግብ ቤት"));
+
+        let json = serde_json::to_string(&builder).unwrap();
+        let decoded: QRBuilder = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded, builder);
+        assert_eq!(
+            decoded.build_payload().unwrap(),
+            builder.build_payload().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_builder_defaults_missing_fields() {
+        let builder: QRBuilder = serde_json::from_value(json!({
+            "merchant_name": "Coffee Shop",
+            "merchant_city": "Addis Ababa",
+            "merchant_category_code": "5812",
+            "schemes": [{ "scheme": "visa", "account_info": "4111111111111111" }],
+        }))
+        .unwrap();
+
+        let expected = QRBuilder::new()
+            .merchant_name("Coffee Shop")
+            .merchant_city("Addis Ababa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::visa("4111111111111111"));
+        assert_eq!(builder, expected);
+
+        for (field, value) in [
+            ("payload_format_indicator", "02"),
+            ("transaction_currency", "ET"),
+        ] {
+            let mut json = serde_json::to_value(&expected).unwrap();
+            json[field] = json!(value);
+            let builder: QRBuilder = serde_json::from_value(json).unwrap();
+            assert!(matches!(
+                builder.build_payload(),
+                Err(QRError::InvalidValue { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_enum_representation() {
+        assert_eq!(
+            serde_json::to_value(SchemeConfig::ips_et("g", "b", "a")).unwrap(),
+            json!({ "scheme": "ips_et", "guid": "g", "bic": "b", "account": "a" })
+        );
+        assert_eq!(
+            serde_json::to_value(SchemeConfig::UnionPay {
+                account_info: "1".to_string()
+            })
+            .unwrap(),
+            json!({ "scheme": "unionpay", "account_info": "1" })
+        );
+        assert_eq!(
+            serde_json::to_value(ConvenienceFee::Prompt).unwrap(),
+            json!({ "type": "prompt" })
+        );
+        assert_eq!(
+            serde_json::to_value(ConvenienceFee::Fixed("5.00".to_string())).unwrap(),
+            json!({ "type": "fixed", "value": "5.00" })
+        );
+
+        assert_eq!(
+            serde_json::to_value(crate::repair::Edit::Transpose { offset: 3 }).unwrap(),
+            json!({ "type": "transpose", "offset": 3 })
+        );
+        assert_eq!(
+            serde_json::to_value(crate::crc::CrcError::MissingTag).unwrap(),
+            json!({ "type": "missing_tag" })
+        );
+
+        let fields: ExtensionFields =
+            serde_json::from_value(json!({ "end_to_end_id": "E2E-1" })).unwrap();
+        assert_eq!(fields.end_to_end_id.as_deref(), Some("E2E-1"));
+        assert!(serde_json::from_value::<EMVTag>(json!({ "id": "59" })).is_err());
+    }
+}
@@ -0,0 +1,621 @@
+//! Parsing of EMVCo QR payloads
+//!
+//! Splits a payload string into its TLV tags and maps the well-known tags back
+//! onto the builder's data model.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::{QRBuilder, fields::SchemeConfig, parser};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let payload = QRBuilder::new()
+//!     .merchant_name("Coffee Shop")
+//!     .merchant_city("Addis Ababa")
+//!     .merchant_category_code("5812")
+//!     .add_scheme(SchemeConfig::visa("4111111111111111"))
+//!     .transaction_amount("25.00")
+//!     .build_payload()?;
+//!
+//! let parsed = parser::parse(&payload)?;
+//! assert_eq!(parsed.merchant_name, "Coffee Shop");
+//! assert_eq!(parsed.transaction_amount.as_deref(), Some("25.00"));
+//! assert!(parsed.is_dynamic());
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::error::{QRError, Result};
+use crate::fields::{AdditionalData, LanguageTemplate, PointOfInitiation, SchemeConfig, Validity};
+use crate::{EMVTag, QRBuilder, constants, crc, tags};
+
+/// Decoded QR payload
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParsedQR {
+    pub payload_format_indicator: String,
+    pub point_of_initiation: Option<String>,
+    /// Merchant account information for supported schemes
+    pub schemes: Vec<SchemeConfig>,
+    pub merchant_category_code: String,
+    pub transaction_currency: String,
+    pub transaction_amount: Option<String>,
+    pub country_code: String,
+    pub merchant_name: String,
+    pub merchant_city: String,
+    pub additional_data: Option<AdditionalData>,
+    pub language_template: Option<LanguageTemplate>,
+    pub transaction_context: Option<String>,
+    pub end_to_end_id: Option<String>,
+    pub validity: Option<Validity>,
+    pub crc: String,
+    /// Tags that are not mapped onto any field above
+    pub unknown_tags: Vec<EMVTag>,
+}
+
+impl ParsedQR {
+    /// Whether the point of initiation marks a dynamic QR code
+    #[must_use]
+    pub fn is_dynamic(&self) -> bool {
+        self.point_of_initiation.as_deref() == Some(constants::DYNAMIC_QR_POI)
+    }
+
+    /// Convert back into a builder carrying the same data
+    #[must_use]
+    pub fn to_builder(&self) -> QRBuilder {
+        let mut builder = QRBuilder::new()
+            .merchant_name(&self.merchant_name)
+            .merchant_city(&self.merchant_city)
+            .merchant_category_code(&self.merchant_category_code);
+
+        if let Some(point_of_initiation) = self
+            .point_of_initiation
+            .as_deref()
+            .and_then(PointOfInitiation::from_code)
+        {
+            builder = builder.point_of_initiation(point_of_initiation);
+        }
+        for scheme in &self.schemes {
+            builder = builder.add_scheme(scheme.clone());
+        }
+        if let Some(ref amount) = self.transaction_amount {
+            builder = builder.transaction_amount(amount);
+        }
+        if let Some(ref data) = self.additional_data {
+            builder = builder.additional_data(data.clone());
+        }
+        if let Some(ref template) = self.language_template {
+            builder = builder.language_template(template.clone());
+        }
+        if let Some(ref context) = self.transaction_context {
+            builder = builder.transaction_context(context);
+        }
+        if let Some(ref id) = self.end_to_end_id {
+            builder = builder.end_to_end_id(id);
+        }
+        if let Some(validity) = self.validity {
+            builder = builder.validity(validity);
+        }
+
+        builder
+    }
+
+    /// Check the validity window, if any, against a clock value supplied by
+    /// the caller
+    ///
+    /// Codes without a validity window never expire.
+    ///
+    /// # Errors
+    /// Returns [`QRError::NotYetValid`] or [`QRError::Expired`] if `now` is
+    /// outside the window.
+    pub fn check_validity(&self, now: u64) -> Result<()> {
+        self.validity.map_or(Ok(()), |validity| validity.check(now))
+    }
+}
+
+/// Split a string into a sequence of TLV tags
+///
+/// Each tag is a two-character ID, a two-digit length and a value of that many
+/// bytes. Works for both the root payload and template values such as tag 62.
+///
+/// # Errors
+/// Returns [`QRError::InvalidFormat`] if the data is truncated, a length is not
+/// numeric, or a value does not end on a character boundary.
+pub fn parse_tlv(data: &str) -> Result<Vec<EMVTag>> {
+    let mut tags = Vec::new();
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let header = data
+            .get(offset..offset + 4)
+            .ok_or_else(|| QRError::InvalidFormat {
+                message: format!("truncated tag header at offset {offset}"),
+            })?;
+
+        if !header.is_ascii() {
+            return Err(QRError::InvalidFormat {
+                message: format!("non-ASCII tag header at offset {offset}"),
+            });
+        }
+
+        let (id, length) = header.split_at(2);
+        if !length.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(QRError::InvalidFormat {
+                message: format!("invalid length '{length}' for tag {id} at offset {offset}"),
+            });
+        }
+        let length: usize = length.parse().map_err(|_| QRError::InvalidFormat {
+            message: format!("invalid length for tag {id} at offset {offset}"),
+        })?;
+
+        let start = offset + 4;
+        let value = data
+            .get(start..start + length)
+            .ok_or_else(|| QRError::InvalidFormat {
+                message: format!("value of tag {id} at offset {offset} exceeds payload"),
+            })?;
+
+        tags.push(EMVTag::new(id, value));
+        offset = start + length;
+    }
+
+    Ok(tags)
+}
+
+/// Parse and verify a complete QR payload
+///
+/// # Errors
+/// Returns an error if the payload is not valid TLV, the CRC does not match, or
+/// a mandatory tag is missing.
+pub fn parse(payload: &str) -> Result<ParsedQR> {
+    let root = parse_tlv(payload)?;
+
+    crc::check_crc(payload)?;
+
+    let mut payload_format_indicator = None;
+    let mut point_of_initiation = None;
+    let mut schemes = Vec::new();
+    let mut merchant_category_code = None;
+    let mut transaction_currency = None;
+    let mut transaction_amount = None;
+    let mut country_code = None;
+    let mut merchant_name = None;
+    let mut merchant_city = None;
+    let mut additional_data = None;
+    let mut language_template = None;
+    let mut transaction_context = None;
+    let mut end_to_end_id = None;
+    let mut validity = None;
+    let mut crc_value = None;
+    let mut unknown_tags = Vec::new();
+
+    for tag in root {
+        match tag.id.as_str() {
+            tags::PAYLOAD_FORMAT_INDICATOR => payload_format_indicator = Some(tag.value),
+            tags::POINT_OF_INITIATION => point_of_initiation = Some(tag.value),
+            tags::VISA => schemes.push(SchemeConfig::visa(tag.value)),
+            tags::MASTERCARD => schemes.push(SchemeConfig::mastercard(tag.value)),
+            tags::UNIONPAY => schemes.push(SchemeConfig::UnionPay {
+                account_info: tag.value,
+            }),
+            tags::IPS_ET => schemes.push(parse_ips_et(&tag.value)?),
+            tags::MERCHANT_CATEGORY_CODE => merchant_category_code = Some(tag.value),
+            tags::TRANSACTION_CURRENCY => transaction_currency = Some(tag.value),
+            tags::TRANSACTION_AMOUNT => transaction_amount = Some(tag.value),
+            tags::COUNTRY_CODE => country_code = Some(tag.value),
+            tags::MERCHANT_NAME => merchant_name = Some(tag.value),
+            tags::MERCHANT_CITY => merchant_city = Some(tag.value),
+            tags::ADDITIONAL_DATA => additional_data = Some(parse_additional_data(&tag.value)?),
+            tags::ALTERNATE_LANGUAGE => {
+                language_template = Some(parse_language_template(&tag.value)?);
+            }
+            tags::TRANSACTION_CONTEXT => transaction_context = Some(tag.value),
+            tags::END_TO_END_ID => end_to_end_id = Some(tag.value),
+            tags::VALIDITY => match parse_validity(&tag.value)? {
+                Some(window) => validity = Some(window),
+                // Another merchant-specific template using the same tag
+                None => unknown_tags.push(tag),
+            },
+            tags::CRC => crc_value = Some(tag.value),
+            _ => unknown_tags.push(tag),
+        }
+    }
+
+    let required = |value: Option<String>, field: &str| {
+        value.ok_or_else(|| QRError::MissingField {
+            field: field.to_string(),
+        })
+    };
+
+    Ok(ParsedQR {
+        payload_format_indicator: required(payload_format_indicator, "payload_format_indicator")?,
+        point_of_initiation,
+        schemes,
+        merchant_category_code: required(merchant_category_code, "category_code")?,
+        transaction_currency: required(transaction_currency, "currency")?,
+        transaction_amount,
+        country_code: required(country_code, "country_code")?,
+        merchant_name: required(merchant_name, "name")?,
+        merchant_city: required(merchant_city, "city")?,
+        additional_data,
+        language_template,
+        transaction_context,
+        end_to_end_id,
+        validity,
+        crc: required(crc_value, "crc")?,
+        unknown_tags,
+    })
+}
+
+/// Parse and verify a complete QR payload, rejecting it if `now` is outside
+/// its validity window
+///
+/// ```
+/// use ethqr_gen::{QRBuilder, error::QRError, fields::{SchemeConfig, Validity}, parser};
+///
+/// let now = 1_760_000_000;
+/// let payload = QRBuilder::new()
+///     .merchant_name("Coffee Shop")
+///     .merchant_city("Addis Ababa")
+///     .merchant_category_code("5812")
+///     .add_scheme(SchemeConfig::visa("4111111111111111"))
+///     .transaction_amount("25.00")
+///     .validity(Validity::for_duration(now, 10 * 60))
+///     .build_payload()?;
+///
+/// assert!(parser::parse_at(&payload, now + 60).is_ok());
+/// assert!(matches!(
+///     parser::parse_at(&payload, now + 10 * 60),
+///     Err(QRError::Expired { .. })
+/// ));
+/// # Ok::<(), QRError>(())
+/// ```
+///
+/// # Errors
+/// Returns the errors of [`parse`], or [`QRError::NotYetValid`] or
+/// [`QRError::Expired`] if `now` is outside the validity window.
+pub fn parse_at(payload: &str, now: u64) -> Result<ParsedQR> {
+    let parsed = parse(payload)?;
+    parsed.check_validity(now)?;
+    Ok(parsed)
+}
+
+/// Parse the validity template, or `None` if tag 90 carries another template
+pub(crate) fn parse_validity(value: &str) -> Result<Option<Validity>> {
+    let mut id = None;
+    let mut not_before = None;
+    let mut expires_at = None;
+
+    for tag in parse_tlv(value)? {
+        match tag.id.as_str() {
+            "00" => id = Some(tag.value),
+            "01" => not_before = Some(tag.value),
+            "02" => expires_at = Some(tag.value),
+            _ => {}
+        }
+    }
+
+    if id.as_deref() != Some(constants::VALIDITY_TEMPLATE_ID) {
+        return Ok(None);
+    }
+
+    let expires_at = expires_at.ok_or_else(|| QRError::MissingField {
+        field: "expires_at".to_string(),
+    })?;
+    Ok(Some(Validity {
+        not_before: not_before
+            .map(|value| parse_timestamp(&value, "not_before"))
+            .transpose()?,
+        expires_at: parse_timestamp(&expires_at, "expires_at")?,
+    }))
+}
+
+fn parse_timestamp(value: &str, field: &str) -> Result<u64> {
+    value
+        .bytes()
+        .all(|b| b.is_ascii_digit())
+        .then(|| value.parse().ok())
+        .flatten()
+        .ok_or_else(|| QRError::InvalidValue {
+            field: field.to_string(),
+            value: value.to_string(),
+        })
+}
+
+pub(crate) fn parse_ips_et(value: &str) -> Result<SchemeConfig> {
+    let mut guid = None;
+    let mut bic = None;
+    let mut account = None;
+
+    for tag in parse_tlv(value)? {
+        match tag.id.as_str() {
+            "00" => guid = Some(tag.value),
+            "01" => bic = Some(tag.value),
+            "02" => account = Some(tag.value),
+            _ => {}
+        }
+    }
+
+    match (guid, bic, account) {
+        (Some(guid), Some(bic), Some(account)) => Ok(SchemeConfig::IPSET { guid, bic, account }),
+        _ => Err(QRError::InvalidFormat {
+            message: format!(
+                "incomplete IPS ET account information in tag {}",
+                tags::IPS_ET
+            ),
+        }),
+    }
+}
+
+fn parse_language_template(value: &str) -> Result<LanguageTemplate> {
+    let mut template = LanguageTemplate::default();
+    let mut has_language = false;
+    let mut has_name = false;
+
+    for tag in parse_tlv(value)? {
+        match tag.id.as_str() {
+            "00" => {
+                template.language_preference = tag.value;
+                has_language = true;
+            }
+            "01" => {
+                template.merchant_name = tag.value;
+                has_name = true;
+            }
+            "02" => template.merchant_city = Some(tag.value),
+            _ => {}
+        }
+    }
+
+    if !has_language || !has_name {
+        return Err(QRError::InvalidFormat {
+            message: format!(
+                "incomplete language template in tag {}",
+                tags::ALTERNATE_LANGUAGE
+            ),
+        });
+    }
+
+    Ok(template)
+}
+
+fn parse_additional_data(value: &str) -> Result<AdditionalData> {
+    let mut data = AdditionalData::new();
+
+    for tag in parse_tlv(value)? {
+        let field = match tag.id.as_str() {
+            "01" => &mut data.bill_number,
+            "02" => &mut data.mobile_number,
+            "03" => &mut data.store_label,
+            "04" => &mut data.loyalty_number,
+            "05" => &mut data.reference_label,
+            "06" => &mut data.customer_label,
+            "07" => &mut data.terminal_number,
+            "08" => &mut data.purpose,
+            "09" => &mut data.additional_customer_data,
+            "10" => &mut data.merchant_tax_id,
+            "11" => &mut data.merchant_channel,
+            "50" => &mut data.due_date,
+            "51" => &mut data.amount_after_due_date,
+            _ => continue,
+        };
+        *field = Some(tag.value);
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crc::CrcError;
+    use crate::id::{CounterGenerator, UlidGenerator};
+
+    fn builder() -> QRBuilder {
+        QRBuilder::new()
+            .merchant_name("Restaurant")
+            .merchant_city("Dire Dawa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::visa("4111111111111111"))
+            .add_scheme(SchemeConfig::ips_et(
+                "581b314e257f41bfbbdc6384daa31d16",
+                "CBETETAA",
+                "10000171234567890",
+            ))
+            .transaction_amount("50.00")
+            .additional_data(
+                AdditionalData::new()
+                    .bill_number("INV-001")
+                    .reference_label("ORDER-123"),
+            )
+            .language_template(LanguageTemplate::new("am", "ምግብ ቤት").merchant_city("ድሬዳዋ"))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let payload = builder().build_payload().unwrap();
+        let parsed = parse(&payload).unwrap();
+
+        assert!(parsed.is_dynamic());
+        assert_eq!(parsed.schemes.len(), 2);
+        assert_eq!(parsed.country_code, constants::ETHIOPIA_COUNTRY_CODE);
+        assert_eq!(
+            parsed
+                .additional_data
+                .as_ref()
+                .unwrap()
+                .reference_label
+                .as_deref(),
+            Some("ORDER-123")
+        );
+        assert_eq!(
+            parsed.language_template.as_ref().unwrap().merchant_name,
+            "ምግብ ቤት"
+        );
+        assert_eq!(parsed.to_builder().build_payload().unwrap(), payload);
+    }
+
+    #[test]
+    fn test_stamped_ids() {
+        let mut references = CounterGenerator::new("T7-", || 1_760_000_000_000);
+        let mut e2e = UlidGenerator::new(|| 1_760_000_000_000, || 42);
+        let payload = builder()
+            .stamp_reference_label(&mut references)
+            .stamp_end_to_end_id(&mut e2e)
+            .build_payload()
+            .unwrap();
+        let parsed = parse(&payload).unwrap();
+
+        let data = parsed.additional_data.as_ref().unwrap();
+        assert_eq!(data.reference_label.as_deref(), Some("T7-1MEEY0000000"));
+        assert_eq!(data.bill_number.as_deref(), Some("INV-001"));
+        assert_eq!(parsed.end_to_end_id.as_deref().map(str::len), Some(26));
+        assert_eq!(parsed.to_builder().build_payload().unwrap(), payload);
+
+        let too_long = builder().stamp_reference_label(&mut e2e).build_payload();
+        assert!(matches!(
+            too_long,
+            Err(QRError::ValueTooLong { ref field, .. }) if field == "reference_label"
+        ));
+    }
+
+    #[test]
+    fn test_explicit_point_of_initiation() {
+        // Static code with a fixed amount, e.g. a parking fee
+        let payload = builder()
+            .point_of_initiation(PointOfInitiation::Static)
+            .build_payload()
+            .unwrap();
+        let parsed = parse(&payload).unwrap();
+        assert!(!parsed.is_dynamic());
+        assert_eq!(parsed.transaction_amount.as_deref(), Some("50.00"));
+        assert_eq!(parsed.to_builder().build_payload().unwrap(), payload);
+        assert!(crate::validation::validate(&payload).issues.is_empty());
+
+        // Dynamic request-to-pay without an amount
+        let request = QRBuilder::new()
+            .merchant_name("Coffee Shop")
+            .merchant_city("Addis Ababa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::visa("4111111111111111"))
+            .point_of_initiation(PointOfInitiation::Dynamic);
+        assert!(matches!(
+            request.build_payload(),
+            Err(QRError::ValidationError { .. })
+        ));
+        let payload = request.end_to_end_id("RTP-1").build_payload().unwrap();
+        assert!(parse(&payload).unwrap().is_dynamic());
+
+        assert!(matches!(
+            builder()
+                .point_of_initiation(PointOfInitiation::Static)
+                .end_to_end_id("RTP-1")
+                .build_payload(),
+            Err(QRError::ValidationError { .. })
+        ));
+
+        // A fixed-amount static code cannot expire either
+        assert!(matches!(
+            builder()
+                .point_of_initiation(PointOfInitiation::Static)
+                .validity(Validity::until(1_760_000_600))
+                .build_payload(),
+            Err(QRError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validity_window() {
+        let now = 1_760_000_000;
+        let payload = builder()
+            .validity(Validity::for_duration(now, 600))
+            .build_payload()
+            .unwrap();
+        let parsed = parse(&payload).unwrap();
+
+        assert_eq!(
+            parsed.validity,
+            Some(Validity {
+                not_before: Some(now),
+                expires_at: now + 600,
+            })
+        );
+        assert!(parsed.unknown_tags.is_empty());
+        assert_eq!(parsed.to_builder().build_payload().unwrap(), payload);
+        assert!(parse_at(&payload, now + 599).is_ok());
+        assert!(matches!(
+            parse_at(&payload, now + 600),
+            Err(QRError::Expired { expires_at, .. }) if expires_at == now + 600
+        ));
+        assert!(matches!(
+            parse_at(&payload, now - 1),
+            Err(QRError::NotYetValid { .. })
+        ));
+
+        // Codes without a window never expire
+        assert!(parse_at(&builder().build_payload().unwrap(), u64::MAX).is_ok());
+
+        assert!(matches!(
+            builder()
+                .validity(Validity {
+                    not_before: Some(10),
+                    expires_at: 10
+                })
+                .build_payload(),
+            Err(QRError::InvalidValue { .. })
+        ));
+
+        // Static codes are reused, so they cannot carry a window
+        assert!(matches!(
+            QRBuilder::new()
+                .merchant_name("Restaurant")
+                .merchant_city("Dire Dawa")
+                .merchant_category_code("5812")
+                .add_scheme(SchemeConfig::visa("4111111111111111"))
+                .validity(Validity::for_duration(now, 600))
+                .build_payload(),
+            Err(QRError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_foreign_template_90_is_unknown() {
+        assert_eq!(parse_validity("0007ACME.CO0102120202ab").unwrap(), None);
+        assert!(matches!(
+            parse_validity("0017ET.ETHQR.VALIDITY0203abc"),
+            Err(QRError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_validity("0017ET.ETHQR.VALIDITY"),
+            Err(QRError::MissingField { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_tlv_errors() {
+        assert!(parse_tlv("000201").is_ok());
+        assert!(parse_tlv("000").is_err());
+        assert!(parse_tlv("00AB01").is_err());
+        assert!(parse_tlv("000501").is_err());
+        // Length ends inside a multi-byte character
+        assert!(parse_tlv("0001é").is_err());
+        assert!(parse_tlv("0aé01").is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_crc() {
+        let mut payload = builder().build_payload().unwrap();
+        payload.replace_range(payload.len() - 4.., "0000");
+
+        assert!(matches!(
+            parse(&payload),
+            Err(QRError::CrcCheckFailed {
+                reason: CrcError::Mismatch { found: 0, .. }
+            })
+        ));
+    }
+}
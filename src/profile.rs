@@ -0,0 +1,276 @@
+//! Merchant profile files
+//!
+//! A profile holds the static parts of a merchant's QR configuration (name,
+//! city, category code, schemes, language template and additional data
+//! defaults) so it can live in version control as TOML, YAML or JSON. Dynamic
+//! codes are then stamped from the profile with just the per-transaction data.
+//!
+//! ```toml
+//! name = "Addis Coffee House"
+//! city = "Addis Ababa"
+//! merchant_category_code = "5812"
+//!
+//! [[schemes]]
+//! scheme = "ips_et"
+//! guid = "581b314e257f41bfbbdc6384daa31d16"
+//! bic = "CBETETAA"
+//! account = "10000171234567890"
+//!
+//! [language_template]
+//! language_preference = "am"
+//! merchant_name = "አዲስ ቡና"
+//!
+//! [additional_data]
+//! store_label = "Bole Branch"
+//! terminal_number = "T01"
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::fields::AdditionalData;
+//! use ethqr_gen::profile::MerchantProfile;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let profile = MerchantProfile::from_json_str(r#"{
+//!     "name": "Addis Coffee House",
+//!     "city": "Addis Ababa",
+//!     "merchant_category_code": "5812",
+//!     "schemes": [{ "scheme": "visa", "account_info": "4111111111111111" }],
+//!     "additional_data": { "store_label": "Bole Branch" }
+//! }"#)?;
+//!
+//! let payload = profile
+//!     .dynamic_builder("150.00", AdditionalData::new().bill_number("INV-42"))
+//!     .build_payload()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::QRBuilder;
+use crate::error::{QRError, Result};
+use crate::fields::{AdditionalData, LanguageTemplate, SchemeConfig};
+
+/// Static merchant configuration loaded from a profile file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MerchantProfile {
+    /// Merchant name (tag 59)
+    pub name: String,
+    /// Merchant city (tag 60)
+    pub city: String,
+    /// Merchant category code (tag 52)
+    #[serde(alias = "mcc")]
+    pub merchant_category_code: String,
+    /// Merchant account information (tags 02-51)
+    pub schemes: Vec<SchemeConfig>,
+    /// Merchant information in an alternate language (tag 64)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_template: Option<LanguageTemplate>,
+    /// Additional data applied to every QR code (tag 62)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_data: Option<AdditionalData>,
+    /// Transaction context (tag 80)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_context: Option<String>,
+}
+
+impl MerchantProfile {
+    /// Parse a profile from TOML
+    ///
+    /// # Errors
+    /// Returns [`QRError::InvalidFormat`] if the document is not a valid profile.
+    pub fn from_toml_str(source: &str) -> Result<Self> {
+        toml::from_str(source).map_err(|e| QRError::InvalidFormat {
+            message: format!("invalid TOML profile: {e}"),
+        })
+    }
+
+    /// Parse a profile from YAML
+    ///
+    /// # Errors
+    /// Returns [`QRError::InvalidFormat`] if the document is not a valid profile.
+    pub fn from_yaml_str(source: &str) -> Result<Self> {
+        serde_yaml::from_str(source).map_err(|e| QRError::InvalidFormat {
+            message: format!("invalid YAML profile: {e}"),
+        })
+    }
+
+    /// Parse a profile from JSON
+    ///
+    /// # Errors
+    /// Returns [`QRError::InvalidFormat`] if the document is not a valid profile.
+    pub fn from_json_str(source: &str) -> Result<Self> {
+        serde_json::from_str(source).map_err(|e| QRError::InvalidFormat {
+            message: format!("invalid JSON profile: {e}"),
+        })
+    }
+
+    /// Load a profile file, choosing the format from its extension
+    /// (`.toml`, `.yaml`/`.yml` or `.json`)
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, has an unknown extension,
+    /// or is not a valid profile.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&source),
+            Some("yaml" | "yml") => Self::from_yaml_str(&source),
+            Some("json") => Self::from_json_str(&source),
+            _ => Err(QRError::InvalidFormat {
+                message: format!("unknown profile format: {}", path.display()),
+            }),
+        }
+    }
+
+    /// Check that the profile builds a valid static QR code
+    ///
+    /// # Errors
+    /// Returns the builder error for the first invalid field.
+    pub fn validate(&self) -> Result<()> {
+        self.to_builder().build_payload().map(|_| ())
+    }
+
+    /// Builder for a static QR code carrying the profile data
+    #[must_use]
+    pub fn to_builder(&self) -> QRBuilder {
+        let mut builder = QRBuilder::new()
+            .merchant_name(&self.name)
+            .merchant_city(&self.city)
+            .merchant_category_code(&self.merchant_category_code);
+
+        for scheme in &self.schemes {
+            builder = builder.add_scheme(scheme.clone());
+        }
+        if let Some(ref template) = self.language_template {
+            builder = builder.language_template(template.clone());
+        }
+        if let Some(ref data) = self.additional_data {
+            builder = builder.additional_data(data.clone());
+        }
+        if let Some(ref context) = self.transaction_context {
+            builder = builder.transaction_context(context);
+        }
+
+        builder
+    }
+
+    /// Builder for a dynamic QR code with the given amount
+    ///
+    /// Fields set in `references` override the profile's additional data
+    /// defaults; unset fields keep the profile values.
+    #[must_use]
+    pub fn dynamic_builder(
+        &self,
+        amount: impl Into<String>,
+        references: AdditionalData,
+    ) -> QRBuilder {
+        let defaults = self.additional_data.clone().unwrap_or_default();
+
+        self.to_builder()
+            .transaction_amount(amount)
+            .additional_data(defaults.overlay(references))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    const TOML: &str = r#"
+name = "Addis Coffee House"
+city = "Addis Ababa"
+mcc = "5812"
+
+[[schemes]]
+scheme = "visa"
+account_info = "4111111111111111"
+
+[[schemes]]
+scheme = "ips_et"
+guid = "581b314e257f41bfbbdc6384daa31d16"
+bic = "CBETETAA"
+account = "10000171234567890"
+
+[language_template]
+language_preference = "am"
+merchant_name = "አዲስ ቡና"
+
+[additional_data]
+store_label = "Bole Branch"
+terminal_number = "T01"
+"#;
+
+    const YAML: &str = r#"
+name: Addis Coffee House
+city: Addis Ababa
+merchant_category_code: "5812"
+schemes:
+  - scheme: visa
+    account_info: "4111111111111111"
+  - scheme: ips_et
+    guid: 581b314e257f41bfbbdc6384daa31d16
+    bic: CBETETAA
+    account: "10000171234567890"
+language_template:
+  language_preference: am
+  merchant_name: አዲስ ቡና
+additional_data:
+  store_label: Bole Branch
+  terminal_number: T01
+"#;
+
+    #[test]
+    fn test_formats_agree() {
+        let toml = MerchantProfile::from_toml_str(TOML).unwrap();
+        let yaml = MerchantProfile::from_yaml_str(YAML).unwrap();
+        let json = MerchantProfile::from_json_str(&serde_json::to_string(&toml).unwrap()).unwrap();
+
+        assert_eq!(toml, yaml);
+        assert_eq!(toml, json);
+        assert_eq!(toml.schemes.len(), 2);
+        toml.validate().unwrap();
+    }
+
+    #[test]
+    fn test_dynamic_builder_overlays_references() {
+        let profile = MerchantProfile::from_toml_str(TOML).unwrap();
+        let payload = profile
+            .dynamic_builder(
+                "75.50",
+                AdditionalData::new()
+                    .bill_number("INV-9")
+                    .terminal_number("T02"),
+            )
+            .build_payload()
+            .unwrap();
+
+        let parsed = parser::parse(&payload).unwrap();
+        assert!(parsed.is_dynamic());
+        assert_eq!(parsed.transaction_amount.as_deref(), Some("75.50"));
+        let data = parsed.additional_data.unwrap();
+        assert_eq!(data.bill_number.as_deref(), Some("INV-9"));
+        assert_eq!(data.terminal_number.as_deref(), Some("T02"));
+        assert_eq!(data.store_label.as_deref(), Some("Bole Branch"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        let result = MerchantProfile::from_toml_str(&format!("{TOML}\nmerchant_id = \"x\"\n"));
+        assert!(matches!(result, Err(QRError::InvalidFormat { .. })));
+
+        let result = MerchantProfile::from_json_str(
+            r#"{"name":"A","city":"B","mcc":"5812","schemes":[{"scheme":"amex","account_info":"1"}]}"#,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Diagnostics for payloads that fail their CRC
+//!
+//! Payloads typed by hand from damaged stickers usually differ from the
+//! original by a single character. [`diagnose_crc`] reports the CRC the
+//! payload should carry and searches single-character substitutions and
+//! adjacent transpositions for candidates that pass both the CRC and
+//! [`validation::validate`], most likely first.
+//!
+//! ```
+//! use ethqr_gen::repair::{Edit, diagnose_crc};
+//!
+//! // "Coffee Shop" typed as "Coffee Sh0p"
+//! let typed = "000201010211021641111111111111115204581253032305802ET5911Coffee Sh0p6011Addis Ababa63048EFB";
+//! let diagnosis = diagnose_crc(typed);
+//!
+//! assert_eq!(diagnosis.expected_crc.as_deref(), Some("DDB6"));
+//! let best = &diagnosis.suggestions[0];
+//! assert_eq!(best.edit, Edit::Substitute { offset: 66, found: '0', replacement: 'o' });
+//! assert!(best.payload.contains("Coffee Shop"));
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::constants;
+use crate::crc::{self, Crc16, CrcError};
+use crate::validation::{self, Severity};
+
+/// Characters tried as replacements: printable ASCII
+const ALPHABET: core::ops::RangeInclusive<u8> = b' '..=b'~';
+
+/// Pairs of characters commonly confused when reading printed payloads
+const CONFUSABLE: &[(char, char)] = &[
+    ('0', 'O'),
+    ('0', 'o'),
+    ('0', 'D'),
+    ('0', 'Q'),
+    ('1', 'I'),
+    ('1', 'l'),
+    ('1', '7'),
+    ('I', 'l'),
+    ('2', 'Z'),
+    ('5', 'S'),
+    ('6', 'G'),
+    ('8', 'B'),
+    ('8', '3'),
+    ('9', 'g'),
+    ('C', 'G'),
+    ('E', 'F'),
+    ('U', 'V'),
+    ('.', ','),
+];
+
+/// A single-character change to a payload
+///
+/// Serialised as `{"type": "substitute", "offset": 3, "found": "0",
+/// "replacement": "O"}` or `{"type": "transpose", "offset": 3}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum Edit {
+    /// Replace the character at byte `offset`
+    Substitute {
+        offset: usize,
+        found: char,
+        replacement: char,
+    },
+    /// Swap the characters at byte `offset` and the one after it
+    Transpose { offset: usize },
+}
+
+impl Edit {
+    /// Relative unlikelihood of the edit: confusable characters, case slips
+    /// and transpositions are cheaper than arbitrary substitutions
+    fn cost(&self) -> u8 {
+        match *self {
+            Edit::Transpose { .. } => 1,
+            Edit::Substitute {
+                found, replacement, ..
+            } => {
+                let confusable = CONFUSABLE.iter().any(|&(a, b)| {
+                    (a, b) == (found, replacement) || (b, a) == (found, replacement)
+                });
+                if confusable || found.eq_ignore_ascii_case(&replacement) {
+                    1
+                } else {
+                    2
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Edit::Substitute {
+                offset,
+                found,
+                replacement,
+            } => write!(
+                f,
+                "replace '{found}' with '{replacement}' at offset {offset}"
+            ),
+            Edit::Transpose { offset } => {
+                write!(f, "swap the character at offset {offset} with the next")
+            }
+        }
+    }
+}
+
+/// A repaired payload and the edit that produced it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Repair {
+    pub payload: String,
+    pub edit: Edit,
+    /// Number of warnings [`validation::validate`] reports for the payload
+    pub warnings: usize,
+}
+
+/// Result of [`diagnose_crc`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CrcDiagnosis {
+    /// Why the payload as given fails, or `None` if it passes
+    pub error: Option<CrcError>,
+    /// CRC the payload body should carry, when the CRC tag can be located
+    pub expected_crc: Option<String>,
+    /// The payload passes, but the CRC's hex digits are lowercase
+    pub lowercase_crc: bool,
+    /// Single-edit repairs passing the CRC and structural validation, most
+    /// likely first
+    pub suggestions: Vec<Repair>,
+}
+
+impl CrcDiagnosis {
+    /// Most likely repair, if any was found
+    #[must_use]
+    pub fn best(&self) -> Option<&Repair> {
+        self.suggestions.first()
+    }
+}
+
+/// Diagnose a payload failing its CRC and suggest likely repairs
+///
+/// Repairs are only searched for payloads that fail [`crc::check_crc`] and
+/// are no longer than [`constants::MAX_QR_LENGTH`].
+#[must_use]
+pub fn diagnose_crc(payload: &str) -> CrcDiagnosis {
+    let error = crc::check_crc(payload).err();
+    let expected_crc = expected_crc(payload, error.as_ref());
+    let found = payload.get(payload.len().saturating_sub(4)..);
+    let lowercase_crc = error.is_none() && found.is_some_and(|crc| crc != crc.to_ascii_uppercase());
+
+    let suggestions = if error.is_some() && payload.len() <= constants::MAX_QR_LENGTH {
+        search(payload)
+    } else {
+        Vec::new()
+    };
+
+    CrcDiagnosis {
+        error,
+        expected_crc,
+        lowercase_crc,
+        suggestions,
+    }
+}
+
+fn expected_crc(payload: &str, error: Option<&CrcError>) -> Option<String> {
+    let crc = match error {
+        Some(CrcError::Mismatch { expected, .. }) => *expected,
+        // The CRC tag was located but its value is unusable or correct
+        None | Some(CrcError::InvalidDigits { .. }) => {
+            let mut crc = Crc16::new();
+            crc.update(&payload.as_bytes()[..payload.len() - 4]);
+            crc.value()
+        }
+        Some(_) => return None,
+    };
+
+    Some(format!("{crc:04X}"))
+}
+
+fn search(payload: &str) -> Vec<Repair> {
+    let mut suggestions = Vec::new();
+    let mut candidate = String::with_capacity(payload.len() + 4);
+    let mut consider = |candidate: &str, edit: Edit| {
+        if crc::check_crc(candidate).is_err() {
+            return;
+        }
+        let report = validation::validate(candidate);
+        if report.is_valid() {
+            let warnings = report
+                .issues
+                .iter()
+                .filter(|issue| issue.severity == Severity::Warning)
+                .count();
+            suggestions.push(Repair {
+                payload: candidate.into(),
+                edit,
+                warnings,
+            });
+        }
+    };
+
+    let mut chars = payload.char_indices().peekable();
+    while let Some((offset, found)) = chars.next() {
+        let (prefix, rest) = payload.split_at(offset);
+        let suffix = &rest[found.len_utf8()..];
+
+        for replacement in ALPHABET.map(char::from).filter(|&c| c != found) {
+            candidate.clear();
+            candidate.push_str(prefix);
+            candidate.push(replacement);
+            candidate.push_str(suffix);
+            consider(
+                &candidate,
+                Edit::Substitute {
+                    offset,
+                    found,
+                    replacement,
+                },
+            );
+        }
+
+        if let Some(&(_, next)) = chars.peek()
+            && next != found
+        {
+            candidate.clear();
+            candidate.push_str(prefix);
+            candidate.push(next);
+            candidate.push(found);
+            candidate.push_str(&suffix[next.len_utf8()..]);
+            consider(&candidate, Edit::Transpose { offset });
+        }
+    }
+
+    suggestions.sort_by_key(|repair| (repair.edit.cost(), repair.warnings));
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QRBuilder;
+    use crate::fields::SchemeConfig;
+
+    fn payload() -> String {
+        QRBuilder::new()
+            .merchant_name("Coffee Shop")
+            .merchant_city("Addis Ababa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::visa("4111111111111111"))
+            .transaction_amount("120.50")
+            .build_payload()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_valid_and_lowercase_crc() {
+        let payload = payload();
+        let diagnosis = diagnose_crc(&payload);
+        assert_eq!(diagnosis.error, None);
+        assert!(!diagnosis.lowercase_crc);
+        assert!(diagnosis.suggestions.is_empty());
+        assert_eq!(
+            diagnosis.expected_crc.as_deref(),
+            Some(&payload[payload.len() - 4..])
+        );
+
+        let lower = format!(
+            "{}{}",
+            &payload[..payload.len() - 4],
+            payload[payload.len() - 4..].to_ascii_lowercase()
+        );
+        // Only meaningful when the CRC contains a letter
+        if lower != payload {
+            assert!(diagnose_crc(&lower).lowercase_crc);
+        }
+    }
+
+    #[test]
+    fn test_repairs_transposition() {
+        let payload = payload();
+        let damaged = payload.replace("120.50", "12.050");
+        let diagnosis = diagnose_crc(&damaged);
+
+        assert!(matches!(diagnosis.error, Some(CrcError::Mismatch { .. })));
+        let best = diagnosis.best().unwrap();
+        assert_eq!(best.payload, payload);
+        assert!(matches!(best.edit, Edit::Transpose { .. }));
+    }
+
+    #[test]
+    fn test_repairs_substitution_in_crc_and_length() {
+        let payload = payload();
+
+        let mut damaged = payload.clone();
+        damaged.replace_range(payload.len() - 1.., "#");
+        let diagnosis = diagnose_crc(&damaged);
+        assert!(matches!(
+            diagnosis.error,
+            Some(CrcError::InvalidDigits { .. })
+        ));
+        assert_eq!(diagnosis.best().unwrap().payload, payload);
+
+        // Merchant name length 11 typed as 1l
+        let damaged = payload.replace("5911Coffee", "591lCoffee");
+        let diagnosis = diagnose_crc(&damaged);
+        assert!(matches!(diagnosis.error, Some(CrcError::Malformed { .. })));
+        assert_eq!(diagnosis.expected_crc, None);
+        assert!(
+            diagnosis
+                .suggestions
+                .iter()
+                .any(|repair| repair.payload == payload)
+        );
+    }
+}
@@ -0,0 +1,422 @@
+//! Local HTTP service
+//!
+//! Exposes the builder, parser and validator as JSON endpoints for callers
+//! that are not written in Rust:
+//!
+//! | Endpoint         | Body                                          | Response                            |
+//! | ---------------- | --------------------------------------------- | ----------------------------------- |
+//! | `POST /qr`       | `{"builder": {...}, "format": "png", "size": 256}` | `{"payload", "crc", "image"}`  |
+//! | `POST /decode`   | `{"payload": "..."}`                          | Decoded fields plus `"dynamic"`     |
+//! | `POST /validate` | `{"payload": "..."}`                          | `{"valid", "issues"}`               |
+//! | `GET /health`    |                                               | `{"status": "ok"}`                  |
+//!
+//! The `builder` object uses the serialised form of [`QRBuilder`]. `format`
+//! is `png` (default), `svg` or `none`, and the image is returned as a
+//! `data:` URI. Failures are reported as
+//! `{"error": {"code": "...", "message": "..."}}` where `code` comes from
+//! [`QRError::code`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::server::QRServer;
+//!
+//! let server = QRServer::new();
+//! let response = server.handle("GET", "/health", b"");
+//! assert_eq!(response.status, 200);
+//! ```
+
+use std::io::Read;
+use std::net::{SocketAddr, ToSocketAddrs};
+
+use image::{DynamicImage, Luma};
+use qrcode::QrCode;
+use qrcode::render::svg;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::error::{QRError, Result};
+use crate::export::{ImageEncoder, ImageFormat};
+use crate::{QRBuilder, data_uri, parser, validation};
+
+/// Default limit on request body size in bytes
+pub const DEFAULT_MAX_BODY_SIZE: usize = 16 * 1024;
+
+/// Default limit on the requested image size in pixels
+pub const DEFAULT_MAX_IMAGE_SIZE: u32 = 2048;
+
+/// Image size used when a request does not specify one
+const DEFAULT_IMAGE_SIZE: u32 = 256;
+
+/// Image format returned by `POST /qr`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    Svg,
+    /// Return only the payload
+    None,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct QRRequest {
+    builder: QRBuilder,
+    #[serde(default)]
+    format: OutputFormat,
+    #[serde(default)]
+    size: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PayloadRequest {
+    payload: String,
+}
+
+/// JSON response produced by [`QRServer::handle`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": { "code": code, "message": message.into() } }),
+        }
+    }
+}
+
+impl From<QRError> for Response {
+    fn from(err: QRError) -> Self {
+        let status = match err {
+            QRError::IoError { .. } | QRError::ImageError { .. } => 500,
+            _ => 422,
+        };
+        Response::error(status, err.code(), err.to_string())
+    }
+}
+
+/// HTTP front end for QR generation, decoding and validation
+#[derive(Debug, Clone)]
+pub struct QRServer {
+    max_body_size: usize,
+    max_image_size: u32,
+}
+
+impl Default for QRServer {
+    fn default() -> Self {
+        Self {
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            max_image_size: DEFAULT_MAX_IMAGE_SIZE,
+        }
+    }
+}
+
+impl QRServer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the largest request body accepted, in bytes
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+
+    /// Set the largest image size a request may ask for, in pixels
+    pub fn max_image_size(mut self, pixels: u32) -> Self {
+        self.max_image_size = pixels;
+        self
+    }
+
+    /// Listen on `addr` and serve requests until the process exits
+    ///
+    /// # Errors
+    /// Returns [`QRError::IoError`] if the address cannot be bound.
+    pub fn serve(self, addr: impl ToSocketAddrs) -> Result<()> {
+        self.bind(addr)?.run();
+        Ok(())
+    }
+
+    /// Bind to `addr` without serving requests yet
+    ///
+    /// # Errors
+    /// Returns [`QRError::IoError`] if the address cannot be bound.
+    pub fn bind(self, addr: impl ToSocketAddrs) -> Result<Listener> {
+        let server = tiny_http::Server::http(addr).map_err(|e| QRError::IoError {
+            message: e.to_string(),
+        })?;
+        let content_type = tiny_http::Header::from_bytes("Content-Type", "application/json")
+            .map_err(|()| QRError::IoError {
+                message: "invalid Content-Type header".to_string(),
+            })?;
+
+        Ok(Listener {
+            server,
+            content_type,
+            config: self,
+        })
+    }
+
+    fn respond(&self, request: &mut tiny_http::Request) -> Response {
+        if request
+            .body_length()
+            .is_some_and(|length| length > self.max_body_size)
+        {
+            return self.body_too_large();
+        }
+
+        // Read one byte past the limit so oversized chunked bodies are caught
+        let mut body = Vec::new();
+        let limit = u64::try_from(self.max_body_size).unwrap_or(u64::MAX);
+        if let Err(e) = request
+            .as_reader()
+            .take(limit.saturating_add(1))
+            .read_to_end(&mut body)
+        {
+            return Response::error(400, "invalid_request", e.to_string());
+        }
+
+        let method = request.method().as_str().to_string();
+        let path = request.url().split('?').next().unwrap_or("").to_string();
+        self.handle(&method, &path, &body)
+    }
+
+    /// Route a request and produce its JSON response
+    #[must_use]
+    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> Response {
+        if body.len() > self.max_body_size {
+            return self.body_too_large();
+        }
+
+        match (method, path) {
+            ("GET", "/health") => Response::ok(json!({ "status": "ok" })),
+            ("POST", "/qr") => self.generate(body),
+            ("POST", "/decode") => decode(body),
+            ("POST", "/validate") => validate(body),
+            (_, "/health" | "/qr" | "/decode" | "/validate") => Response::error(
+                405,
+                "method_not_allowed",
+                format!("{method} is not supported for {path}"),
+            ),
+            _ => Response::error(404, "not_found", format!("no endpoint at {path}")),
+        }
+    }
+
+    fn body_too_large(&self) -> Response {
+        Response::error(
+            413,
+            "body_too_large",
+            format!("request body exceeds {} bytes", self.max_body_size),
+        )
+    }
+
+    fn generate(&self, body: &[u8]) -> Response {
+        let request: QRRequest = match parse_body(body) {
+            Ok(request) => request,
+            Err(response) => return response,
+        };
+
+        let size = request.size.unwrap_or(DEFAULT_IMAGE_SIZE);
+        if size == 0 || size > self.max_image_size {
+            return QRError::InvalidValue {
+                field: "size".to_string(),
+                value: size.to_string(),
+            }
+            .into();
+        }
+
+        let result = request.builder.build_payload().and_then(|payload| {
+            let image = match request.format {
+                OutputFormat::Png => Some(("png", png_data_uri(&payload, size)?)),
+                OutputFormat::Svg => Some(("svg", svg_data_uri(&payload, size)?)),
+                OutputFormat::None => None,
+            };
+            Ok((payload, image))
+        });
+
+        match result {
+            Ok((payload, image)) => Response::ok(json!({
+                "crc": payload[payload.len() - 4..],
+                "payload": payload,
+                "image": image.map(|(format, data_uri)| {
+                    json!({ "format": format, "data_uri": data_uri })
+                }),
+            })),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+/// A bound [`QRServer`], returned by [`QRServer::bind`]
+pub struct Listener {
+    server: tiny_http::Server,
+    content_type: tiny_http::Header,
+    config: QRServer,
+}
+
+impl Listener {
+    /// Address the listener is bound to
+    #[must_use]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Serve requests until the process exits
+    pub fn run(self) {
+        for mut request in self.server.incoming_requests() {
+            let response = self.config.respond(&mut request);
+            let body = response.body.to_string();
+
+            // A client that disconnected early is not a server failure
+            let _ = request.respond(
+                tiny_http::Response::from_string(body)
+                    .with_status_code(response.status)
+                    .with_header(self.content_type.clone()),
+            );
+        }
+    }
+}
+
+fn decode(body: &[u8]) -> Response {
+    let request: PayloadRequest = match parse_body(body) {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+
+    match parser::parse(&request.payload) {
+        Ok(parsed) => {
+            let mut body = json!(parsed);
+            body["dynamic"] = Value::Bool(parsed.is_dynamic());
+            Response::ok(body)
+        }
+        Err(e) => e.into(),
+    }
+}
+
+fn validate(body: &[u8]) -> Response {
+    let request: PayloadRequest = match parse_body(body) {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+
+    let report = validation::validate(&request.payload);
+    Response::ok(json!({ "valid": report.is_valid(), "issues": report.issues }))
+}
+
+fn png_data_uri(payload: &str, size: u32) -> Result<String> {
+    let image = QrCode::new(payload)?
+        .render::<Luma<u8>>()
+        .min_dimensions(size, size)
+        .build();
+
+    let format = ImageFormat::Png;
+    let bytes = ImageEncoder::new(format).encode(&DynamicImage::ImageLuma8(image))?;
+
+    Ok(data_uri::base64_data_uri(format.mime_type(), &bytes))
+}
+
+fn svg_data_uri(payload: &str, size: u32) -> Result<String> {
+    let svg = QrCode::new(payload)?
+        .render::<svg::Color>()
+        .min_dimensions(size, size)
+        .build();
+
+    Ok(data_uri::svg_data_uri(&svg))
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &[u8]) -> std::result::Result<T, Response> {
+    serde_json::from_slice(body)
+        .map_err(|e| Response::error(400, "invalid_request", format!("invalid JSON body: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILDER: &str = r#"{
+        "merchant_name": "Coffee Shop",
+        "merchant_city": "Addis Ababa",
+        "merchant_category_code": "5812",
+        "schemes": [{ "scheme": "visa", "account_info": "4111111111111111" }],
+        "transaction_amount": "25.00"
+    }"#;
+
+    #[test]
+    fn test_generate_then_decode() {
+        let server = QRServer::new();
+        let body = format!(r#"{{"builder": {BUILDER}, "format": "svg", "size": 128}}"#);
+        let response = server.handle("POST", "/qr", body.as_bytes());
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["image"]["format"], "svg");
+        assert!(
+            response.body["image"]["data_uri"]
+                .as_str()
+                .unwrap()
+                .starts_with("data:image/svg+xml,")
+        );
+
+        let payload = response.body["payload"].as_str().unwrap();
+        let request = json!({ "payload": payload }).to_string();
+        let decoded = server.handle("POST", "/decode", request.as_bytes());
+        assert_eq!(decoded.status, 200);
+        assert_eq!(decoded.body["merchant_name"], "Coffee Shop");
+        assert_eq!(decoded.body["dynamic"], true);
+        assert_eq!(decoded.body["crc"], response.body["crc"]);
+
+        let report = server.handle("POST", "/validate", request.as_bytes());
+        assert_eq!(report.body["valid"], true);
+    }
+
+    #[test]
+    fn test_error_responses() {
+        let server = QRServer::new().max_body_size(64);
+
+        let response = server.handle("POST", "/qr", &[b' '; 65]);
+        assert_eq!(response.status, 413);
+        assert_eq!(response.body["error"]["code"], "body_too_large");
+
+        let response = server.handle("POST", "/decode", b"{not json");
+        assert_eq!(response.status, 400);
+        assert_eq!(response.body["error"]["code"], "invalid_request");
+
+        let response = server.handle("POST", "/decode", br#"{"payload": "000201"}"#);
+        assert_eq!(response.status, 422);
+        assert_eq!(response.body["error"]["code"], "invalid_crc");
+
+        assert_eq!(server.handle("GET", "/qr", b"").status, 405);
+        assert_eq!(server.handle("GET", "/missing", b"").status, 404);
+    }
+
+    #[test]
+    fn test_builder_errors_use_error_codes() {
+        let server = QRServer::new();
+        let body = r#"{"builder": {"merchant_name": "A very long merchant name here", "merchant_city": "Addis", "merchant_category_code": "5812"}}"#;
+        let response = server.handle("POST", "/qr", body.as_bytes());
+
+        assert_eq!(response.status, 422);
+        assert_eq!(response.body["error"]["code"], "value_too_long");
+
+        let body = format!(r#"{{"builder": {BUILDER}, "size": 100000}}"#);
+        let response = server.handle("POST", "/qr", body.as_bytes());
+        assert_eq!(response.body["error"]["code"], "invalid_value");
+
+        let mut builder: Value = serde_json::from_str(BUILDER).unwrap();
+        builder["transaction_currency"] = json!("ABC");
+        let body = json!({ "builder": builder }).to_string();
+        let response = server.handle("POST", "/qr", body.as_bytes());
+        assert_eq!(response.status, 422);
+        assert_eq!(response.body["error"]["code"], "invalid_value");
+    }
+}
@@ -0,0 +1,596 @@
+//! Signed payloads for tamper detection (requires `signing` feature)
+//!
+//! The CRC only catches accidental damage: anyone who edits a printed or
+//! displayed code can recompute it. A signed payload carries two extra
+//! templates just before the CRC:
+//!
+//! - tag 91: `00` = [`constants::SIGNATURE_TEMPLATE_ID`], `01` = algorithm
+//!   code (`HS256` or `EdDSA`), `02` = key id
+//! - tag 92: the base64url signature, split into sub-tags `00`, `01`, ... of
+//!   at most 45 characters each
+//!
+//! The signature covers every byte before tag 92, so the algorithm and key id
+//! in tag 91 cannot be swapped without invalidating it. Wallets that don't know
+//! the templates treat them as unknown tags, so signed codes still scan.
+//!
+//! ```
+//! use ethqr_gen::{QRBuilder, fields::SchemeConfig};
+//! use ethqr_gen::signing::{HmacSigner, SignatureStatus, verify_signature};
+//!
+//! let signer = HmacSigner::new("till-7", b"merchant secret");
+//! let payload = QRBuilder::new()
+//!     .merchant_name("Coffee Shop")
+//!     .merchant_city("Addis Ababa")
+//!     .merchant_category_code("5812")
+//!     .add_scheme(SchemeConfig::visa("4111111111111111"))
+//!     .transaction_amount("120.50")
+//!     .build_signed_payload(&signer)?;
+//!
+//! let status = verify_signature(&payload, &|key_id: &str, _| {
+//!     (key_id == "till-7").then(|| signer.verification_key())
+//! });
+//! assert!(status.is_valid());
+//!
+//! let tampered = payload.replace("120.50", "12.50");
+//! assert!(!verify_signature(&tampered, &|_: &str, _| Some(signer.verification_key())).is_valid());
+//! # Ok::<(), ethqr_gen::error::QRError>(())
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use ed25519_dalek::Signer as _;
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+
+use crate::constants;
+use crate::encoder::PayloadWriter;
+use crate::error::{QRError, Result};
+use crate::parser::parse_tlv;
+use crate::tags;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Maximum length of a key id
+pub const MAX_KEY_ID_LEN: usize = 32;
+
+/// Characters per signature sub-tag in tag 92
+const SIGNATURE_CHUNK_LEN: usize = 45;
+
+/// Maximum base64url signature length that fits in tag 92
+const MAX_SIGNATURE_LEN: usize = 2 * SIGNATURE_CHUNK_LEN;
+
+/// Signature algorithm, named by its JOSE code in the payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// HMAC-SHA256 with a key shared between merchant and verifier
+    HmacSha256,
+    /// Ed25519 with the merchant's private key
+    Ed25519,
+}
+
+impl Algorithm {
+    /// Code written to sub-tag 01 of tag 91
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Algorithm::HmacSha256 => "HS256",
+            Algorithm::Ed25519 => "EdDSA",
+        }
+    }
+
+    /// Look up an algorithm by its code
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "HS256" => Some(Algorithm::HmacSha256),
+            "EdDSA" => Some(Algorithm::Ed25519),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Produces signatures for [`QRBuilder::build_signed_payload`](crate::QRBuilder::build_signed_payload)
+///
+/// Implement this to sign with keys held elsewhere, e.g. in an HSM.
+pub trait Signer {
+    /// Algorithm the signature is made with
+    fn algorithm(&self) -> Algorithm;
+
+    /// Identifies the key to verifiers, at most [`MAX_KEY_ID_LEN`] characters
+    fn key_id(&self) -> &str;
+
+    /// Sign `message`, returning the raw signature bytes
+    ///
+    /// # Errors
+    /// Returns an error if the signature cannot be produced.
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// HMAC-SHA256 signer
+#[derive(Clone)]
+pub struct HmacSigner {
+    key_id: String,
+    key: Vec<u8>,
+}
+
+impl HmacSigner {
+    #[must_use]
+    pub fn new(key_id: impl Into<String>, key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            key: key.as_ref().to_vec(),
+        }
+    }
+
+    /// Key verifiers need to check this signer's signatures
+    #[must_use]
+    pub fn verification_key(&self) -> VerificationKey {
+        VerificationKey::Hmac(self.key.clone())
+    }
+}
+
+impl fmt::Debug for HmacSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HmacSigner")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Signer for HmacSigner {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::HmacSha256
+    }
+
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let mut mac = hmac(&self.key)?;
+        mac.update(message);
+        Ok(mac.finalize().into_bytes().to_vec())
+    }
+}
+
+/// Ed25519 signer
+#[derive(Clone)]
+pub struct Ed25519Signer {
+    key_id: String,
+    key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519Signer {
+    #[must_use]
+    pub fn new(key_id: impl Into<String>, key: ed25519_dalek::SigningKey) -> Self {
+        Self {
+            key_id: key_id.into(),
+            key,
+        }
+    }
+
+    /// Create a signer from a 32-byte secret key
+    #[must_use]
+    pub fn from_bytes(key_id: impl Into<String>, secret: &[u8; 32]) -> Self {
+        Self::new(key_id, ed25519_dalek::SigningKey::from_bytes(secret))
+    }
+
+    /// Public key verifiers need to check this signer's signatures
+    #[must_use]
+    pub fn verification_key(&self) -> VerificationKey {
+        VerificationKey::Ed25519(self.key.verifying_key())
+    }
+}
+
+impl fmt::Debug for Ed25519Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ed25519Signer")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Signer for Ed25519Signer {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Ed25519
+    }
+
+    fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.key.sign(message).to_bytes().to_vec())
+    }
+}
+
+/// Key used to check a signature
+#[derive(Clone, PartialEq, Eq)]
+pub enum VerificationKey {
+    /// Shared HMAC-SHA256 key
+    Hmac(Vec<u8>),
+    /// Ed25519 public key
+    Ed25519(ed25519_dalek::VerifyingKey),
+}
+
+impl VerificationKey {
+    /// Ed25519 public key from its 32-byte encoding
+    ///
+    /// # Errors
+    /// Returns [`QRError::InvalidValue`] if the bytes are not a valid point.
+    pub fn ed25519_from_bytes(public: &[u8; 32]) -> Result<Self> {
+        ed25519_dalek::VerifyingKey::from_bytes(public)
+            .map(VerificationKey::Ed25519)
+            .map_err(|_| QRError::InvalidValue {
+                field: "public_key".to_string(),
+                value: URL_SAFE_NO_PAD.encode(public),
+            })
+    }
+
+    fn algorithm(&self) -> Algorithm {
+        match self {
+            VerificationKey::Hmac(_) => Algorithm::HmacSha256,
+            VerificationKey::Ed25519(_) => Algorithm::Ed25519,
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            VerificationKey::Hmac(key) => hmac(key).is_ok_and(|mut mac| {
+                mac.update(message);
+                mac.verify_slice(signature).is_ok()
+            }),
+            VerificationKey::Ed25519(key) => ed25519_dalek::Signature::from_slice(signature)
+                .is_ok_and(|signature| key.verify_strict(message, &signature).is_ok()),
+        }
+    }
+}
+
+impl fmt::Debug for VerificationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationKey::Hmac(_) => f.write_str("Hmac(..)"),
+            VerificationKey::Ed25519(key) => f.debug_tuple("Ed25519").field(key).finish(),
+        }
+    }
+}
+
+/// Looks up the key for the key id and algorithm named in a payload
+///
+/// Implemented for closures `Fn(&str, Algorithm) -> Option<VerificationKey>`.
+pub trait KeyResolver {
+    fn resolve(&self, key_id: &str, algorithm: Algorithm) -> Option<VerificationKey>;
+}
+
+impl<F> KeyResolver for F
+where
+    F: Fn(&str, Algorithm) -> Option<VerificationKey>,
+{
+    fn resolve(&self, key_id: &str, algorithm: Algorithm) -> Option<VerificationKey> {
+        self(key_id, algorithm)
+    }
+}
+
+/// Outcome of [`verify_signature`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The payload carries no signature template
+    Unsigned,
+    /// The signature matches the resolved key
+    Valid {
+        key_id: String,
+        algorithm: Algorithm,
+    },
+    /// The payload was altered or signed with a different key
+    Invalid {
+        key_id: String,
+        algorithm: Algorithm,
+    },
+    /// The resolver has no key for `key_id`
+    UnknownKey {
+        key_id: String,
+        algorithm: Algorithm,
+    },
+    /// The signature templates are malformed or misplaced
+    Malformed { reason: String },
+}
+
+impl SignatureStatus {
+    /// Whether the signature was checked and matches
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        matches!(self, SignatureStatus::Valid { .. })
+    }
+}
+
+/// Check the signature of a payload against keys from `resolver`
+///
+/// Only the signature is checked; use [`parser::parse`](crate::parser::parse)
+/// for the CRC and the other fields.
+#[must_use]
+pub fn verify_signature(payload: &str, resolver: &impl KeyResolver) -> SignatureStatus {
+    match check(payload, resolver) {
+        Ok(status) => status,
+        Err(reason) => SignatureStatus::Malformed { reason },
+    }
+}
+
+fn check(
+    payload: &str,
+    resolver: &impl KeyResolver,
+) -> core::result::Result<SignatureStatus, String> {
+    let root = parse_tlv(payload).map_err(|e| e.to_string())?;
+
+    let mut signed_len = 0;
+    let mut rest = root.iter();
+    let (algorithm, key_id) = loop {
+        let Some(tag) = rest.next() else {
+            return Ok(SignatureStatus::Unsigned);
+        };
+        signed_len += 4 + tag.value.len();
+        if tag.id == tags::SIGNATURE
+            && let Some(header) = parse_header(&tag.value)?
+        {
+            break header;
+        }
+    };
+
+    let signature = match rest.next() {
+        Some(tag) if tag.id == tags::SIGNATURE_VALUE => decode_signature(&tag.value)?,
+        _ => {
+            return Err(format!(
+                "tag {} must follow tag {}",
+                tags::SIGNATURE_VALUE,
+                tags::SIGNATURE
+            ));
+        }
+    };
+    match (rest.next(), rest.next()) {
+        (Some(tag), None) if tag.id == tags::CRC => {}
+        _ => {
+            return Err(format!(
+                "only the CRC may follow tag {}",
+                tags::SIGNATURE_VALUE
+            ));
+        }
+    }
+
+    let Some(key) = resolver.resolve(&key_id, algorithm) else {
+        return Ok(SignatureStatus::UnknownKey { key_id, algorithm });
+    };
+    let message = &payload.as_bytes()[..signed_len];
+    if key.algorithm() == algorithm && key.verify(message, &signature) {
+        Ok(SignatureStatus::Valid { key_id, algorithm })
+    } else {
+        Ok(SignatureStatus::Invalid { key_id, algorithm })
+    }
+}
+
+/// Algorithm and key id from tag 91, or `None` if it carries another template
+fn parse_header(value: &str) -> core::result::Result<Option<(Algorithm, String)>, String> {
+    let mut id = None;
+    let mut algorithm = None;
+    let mut key_id = None;
+
+    for tag in parse_tlv(value).map_err(|e| e.to_string())? {
+        match tag.id.as_str() {
+            "00" => id = Some(tag.value),
+            "01" => algorithm = Some(tag.value),
+            "02" => key_id = Some(tag.value),
+            _ => {}
+        }
+    }
+
+    if id.as_deref() != Some(constants::SIGNATURE_TEMPLATE_ID) {
+        return Ok(None);
+    }
+
+    let algorithm = algorithm.ok_or("missing signature algorithm")?;
+    let algorithm = Algorithm::from_code(&algorithm)
+        .ok_or_else(|| format!("unsupported signature algorithm {algorithm}"))?;
+    let key_id = key_id.ok_or("missing key id")?;
+    Ok(Some((algorithm, key_id)))
+}
+
+fn decode_signature(value: &str) -> core::result::Result<Vec<u8>, String> {
+    let encoded: String = parse_tlv(value)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|tag| tag.value)
+        .collect();
+    URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|e| format!("invalid signature encoding: {e}"))
+}
+
+/// Append tag 91, then sign everything written so far and append tag 92
+pub(crate) fn write_signature(writer: &mut PayloadWriter<'_>, signer: &impl Signer) -> Result<()> {
+    let key_id = signer.key_id();
+    if key_id.is_empty()
+        || !key_id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+    {
+        return Err(QRError::InvalidValue {
+            field: "key_id".to_string(),
+            value: key_id.to_string(),
+        });
+    }
+    if key_id.len() > MAX_KEY_ID_LEN {
+        return Err(QRError::ValueTooLong {
+            field: "key_id".to_string(),
+            length: key_id.len(),
+            max_length: MAX_KEY_ID_LEN,
+        });
+    }
+
+    writer.template(
+        tags::SIGNATURE,
+        &[
+            ("00", Some(constants::SIGNATURE_TEMPLATE_ID)),
+            ("01", Some(signer.algorithm().code())),
+            ("02", Some(key_id)),
+        ],
+    )?;
+
+    if writer.as_bytes().len() < writer.len() {
+        return Err(QRError::PayloadTooLong {
+            length: writer.len(),
+        });
+    }
+
+    let signature = URL_SAFE_NO_PAD.encode(signer.sign(writer.as_bytes())?);
+    if signature.len() > MAX_SIGNATURE_LEN {
+        return Err(QRError::ValueTooLong {
+            field: "signature".to_string(),
+            length: signature.len(),
+            max_length: MAX_SIGNATURE_LEN,
+        });
+    }
+
+    let (first, second) = signature.split_at(signature.len().min(SIGNATURE_CHUNK_LEN));
+    writer.template(
+        tags::SIGNATURE_VALUE,
+        &[
+            ("00", Some(first)),
+            ("01", (!second.is_empty()).then_some(second)),
+        ],
+    )
+}
+
+fn hmac(key: &[u8]) -> Result<HmacSha256> {
+    <HmacSha256 as KeyInit>::new_from_slice(key).map_err(|e| QRError::BuilderError {
+        message: format!("Invalid HMAC key: {e}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QRBuilder;
+    use crate::crc::{calculate_crc16, verify_crc};
+    use crate::fields::SchemeConfig;
+    use crate::{parser, validation};
+
+    fn builder() -> QRBuilder {
+        QRBuilder::new()
+            .merchant_name("Coffee Shop")
+            .merchant_city("Addis Ababa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::visa("4111111111111111"))
+            .transaction_amount("120.50")
+    }
+
+    fn resolver(key: VerificationKey) -> impl KeyResolver {
+        move |key_id: &str, _| (key_id == "till-7").then(|| key.clone())
+    }
+
+    fn resolver_for_any(key: VerificationKey) -> impl KeyResolver {
+        move |_: &str, _| Some(key.clone())
+    }
+
+    #[test]
+    fn test_hmac_round_trip() {
+        let signer = HmacSigner::new("till-7", b"merchant secret");
+        let payload = builder().build_signed_payload(&signer).unwrap();
+
+        assert!(verify_crc(&payload));
+        assert!(payload.contains("91350012ET.ETHQR.SIG0105HS2560206till-7"));
+        let parsed = parser::parse(&payload).unwrap();
+        assert_eq!(parsed.merchant_name, "Coffee Shop");
+        assert!(validation::validate(&payload).is_valid());
+
+        let resolver = resolver(signer.verification_key());
+        assert_eq!(
+            verify_signature(&payload, &resolver),
+            SignatureStatus::Valid {
+                key_id: "till-7".to_string(),
+                algorithm: Algorithm::HmacSha256,
+            }
+        );
+
+        let other = HmacSigner::new("till-7", b"other secret");
+        assert!(matches!(
+            verify_signature(&payload, &resolver_for_any(other.verification_key())),
+            SignatureStatus::Invalid { .. }
+        ));
+
+        // The key id in tag 91 is signed too
+        let body = payload[..payload.len() - 4].replace("0206till-7", "0206till-8");
+        let relabelled = format!("{body}{}", calculate_crc16(&body));
+        assert!(matches!(
+            verify_signature(&relabelled, &resolver_for_any(signer.verification_key())),
+            SignatureStatus::Invalid { .. }
+        ));
+    }
+
+    #[test]
+    fn test_ed25519_detects_tampering() {
+        let signer = Ed25519Signer::from_bytes("till-7", &[7; 32]);
+        let payload = builder().build_signed_payload(&signer).unwrap();
+        let resolver = resolver(signer.verification_key());
+        assert!(verify_signature(&payload, &resolver).is_valid());
+
+        // Edit the amount and recompute the CRC, as an attacker would
+        let body = payload[..payload.len() - 4].replace("5406120.50", "540612.500");
+        let tampered = format!("{body}{}", calculate_crc16(&body));
+        assert!(verify_crc(&tampered));
+        assert!(matches!(
+            verify_signature(&tampered, &resolver),
+            SignatureStatus::Invalid { .. }
+        ));
+
+        // A shared key is never accepted for an Ed25519 signature
+        let hmac = resolver_for_any(VerificationKey::Hmac(b"secret".to_vec()));
+        assert!(matches!(
+            verify_signature(&payload, &hmac),
+            SignatureStatus::Invalid { .. }
+        ));
+    }
+
+    #[test]
+    fn test_unsigned_unknown_and_malformed() {
+        let unsigned = builder().build_payload().unwrap();
+        let signer = HmacSigner::new("till-7", b"merchant secret");
+        assert_eq!(
+            verify_signature(&unsigned, &resolver(signer.verification_key())),
+            SignatureStatus::Unsigned
+        );
+
+        let payload = builder().build_signed_payload(&signer).unwrap();
+        assert!(matches!(
+            verify_signature(&payload, &|_: &str, _| None),
+            SignatureStatus::UnknownKey { .. }
+        ));
+
+        // A field after the signature would not be covered by it
+        let body = format!("{}9903abc6304", &payload[..payload.len() - 8]);
+        let moved = format!("{body}{}", calculate_crc16(&body));
+        assert!(matches!(
+            verify_signature(&moved, &resolver(signer.verification_key())),
+            SignatureStatus::Malformed { .. }
+        ));
+
+        let long_id = HmacSigner::new("k".repeat(MAX_KEY_ID_LEN + 1), b"secret");
+        assert!(matches!(
+            builder().build_signed_payload(&long_id),
+            Err(QRError::ValueTooLong { .. })
+        ));
+        let bad_id = HmacSigner::new("till 7", b"secret");
+        assert!(matches!(
+            builder().build_signed_payload(&bad_id),
+            Err(QRError::InvalidValue { .. })
+        ));
+    }
+}
@@ -0,0 +1,397 @@
+//! Coloured and styled QR code rendering
+//!
+//! Renders QR codes with custom colours, module shapes and finder pattern
+//! ("eye") styles. Colour combinations are checked for sufficient contrast
+//! before rendering so that branded codes remain scannable.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::{QRBuilder, fields::SchemeConfig};
+//! use ethqr_gen::style::{EyeStyle, ModuleShape, QRStyle};
+//! use image::Rgba;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let style = QRStyle::new()
+//!     .foreground(Rgba([0x1a, 0x23, 0x7e, 0xff]))
+//!     .module_shape(ModuleShape::Rounded)
+//!     .eye_style(EyeStyle::Circle);
+//!
+//! let qr_image = QRBuilder::new()
+//!     .merchant_name("Coffee Shop")
+//!     .merchant_city("Addis Ababa")
+//!     .merchant_category_code("5812")
+//!     .add_scheme(SchemeConfig::visa("4111111111111111"))
+//!     .build_styled_image(&style)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use image::{Rgba, RgbaImage};
+use qrcode::{Color, QrCode};
+
+use crate::constants;
+use crate::error::{QRError, Result};
+
+/// Minimum contrast ratio between foreground and background colours
+///
+/// This is the WCAG AA threshold for normal text, which most camera-based
+/// scanners handle reliably.
+pub const MIN_CONTRAST_RATIO: f64 = 4.5;
+
+/// Size of a finder pattern in modules
+const FINDER_SIZE: usize = 7;
+
+/// Shape used to draw data modules
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleShape {
+    #[default]
+    Square,
+    /// Circular dots
+    Dot,
+    /// Squares with rounded corners where they do not touch a neighbour
+    Rounded,
+}
+
+/// Shape used to draw the three finder patterns
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EyeStyle {
+    #[default]
+    Square,
+    /// Square frame and pupil with rounded corners
+    Rounded,
+    /// Circular frame and pupil
+    Circle,
+}
+
+/// Styling options for QR code images
+#[derive(Debug, Clone)]
+pub struct QRStyle {
+    foreground: Rgba<u8>,
+    background: Rgba<u8>,
+    module_shape: ModuleShape,
+    eye_style: EyeStyle,
+    module_size: u32,
+    quiet_zone: u32,
+}
+
+impl Default for QRStyle {
+    fn default() -> Self {
+        Self {
+            foreground: Rgba([0, 0, 0, 255]),
+            background: Rgba([255, 255, 255, 255]),
+            module_shape: ModuleShape::default(),
+            eye_style: EyeStyle::default(),
+            module_size: constants::DEFAULT_QRIMAGE_SIZE,
+            quiet_zone: 4,
+        }
+    }
+}
+
+impl QRStyle {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the colour of dark modules
+    pub fn foreground(mut self, color: Rgba<u8>) -> Self {
+        self.foreground = color;
+        self
+    }
+
+    /// Set the colour of light modules and the quiet zone
+    pub fn background(mut self, color: Rgba<u8>) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// Set the shape of data modules
+    pub fn module_shape(mut self, shape: ModuleShape) -> Self {
+        self.module_shape = shape;
+        self
+    }
+
+    /// Set the style of the finder patterns
+    pub fn eye_style(mut self, style: EyeStyle) -> Self {
+        self.eye_style = style;
+        self
+    }
+
+    /// Set the size of a single module in pixels
+    pub fn module_size(mut self, size: u32) -> Self {
+        self.module_size = size;
+        self
+    }
+
+    /// Set the width of the quiet zone in modules
+    pub fn quiet_zone(mut self, modules: u32) -> Self {
+        self.quiet_zone = modules;
+        self
+    }
+
+    /// Contrast ratio between foreground and background
+    ///
+    /// Translucent colours are composited onto white, assuming the code is
+    /// printed or displayed on a white surface.
+    #[must_use]
+    pub fn contrast_ratio(&self) -> f64 {
+        let background = composite(self.background, [255.0; 3]);
+        let foreground = composite(self.foreground, background);
+
+        let (fg, bg) = (luminance(foreground), luminance(background));
+        (fg.max(bg) + 0.05) / (fg.min(bg) + 0.05)
+    }
+
+    /// Check that the style produces a scannable code
+    ///
+    /// # Errors
+    /// Returns an error if the module size is zero, the foreground is lighter
+    /// than the background, or the contrast ratio is below
+    /// [`MIN_CONTRAST_RATIO`].
+    pub fn validate(&self) -> Result<()> {
+        if self.module_size == 0 {
+            return Err(QRError::InvalidValue {
+                field: "module_size".to_string(),
+                value: self.module_size.to_string(),
+            });
+        }
+
+        let background = composite(self.background, [255.0; 3]);
+        let foreground = composite(self.foreground, background);
+        if luminance(foreground) > luminance(background) {
+            return Err(QRError::ValidationError {
+                message: "foreground colour must be darker than background".to_string(),
+            });
+        }
+
+        let ratio = self.contrast_ratio();
+        if ratio < MIN_CONTRAST_RATIO {
+            return Err(QRError::InsufficientContrast {
+                ratio,
+                min_ratio: MIN_CONTRAST_RATIO,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Render a QR code using this style
+    ///
+    /// # Errors
+    /// Returns an error if the style fails [`QRStyle::validate`].
+    pub fn render(&self, qr_code: &QrCode) -> Result<RgbaImage> {
+        self.validate()?;
+
+        let width = qr_code.width();
+        let colors = qr_code.to_colors();
+        let quiet_zone = self.quiet_zone as usize;
+        let scale = self.module_size as usize;
+        let modules = width + 2 * quiet_zone;
+        let size = u32::try_from(modules * scale).map_err(|_| QRError::ImageError {
+            message: "image dimensions overflow".to_string(),
+        })?;
+
+        let is_dark = |x: isize, y: isize| -> bool {
+            usize::try_from(x)
+                .ok()
+                .zip(usize::try_from(y).ok())
+                .is_some_and(|(x, y)| {
+                    x < width && y < width && colors[y * width + x] == Color::Dark
+                })
+        };
+
+        let finders = [(0, 0), (width - FINDER_SIZE, 0), (0, width - FINDER_SIZE)];
+
+        let image = RgbaImage::from_fn(size, size, |px, py| {
+            // Position in module units, sampled at the pixel centre
+            let fx = (f64::from(px) + 0.5) / scale as f64 - quiet_zone as f64;
+            let fy = (f64::from(py) + 0.5) / scale as f64 - quiet_zone as f64;
+            if fx < 0.0 || fy < 0.0 || fx >= width as f64 || fy >= width as f64 {
+                return self.background;
+            }
+
+            let (mx, my) = (fx as usize, fy as usize);
+
+            let dark = if let Some(&(ox, oy)) = finders.iter().find(|&&(ox, oy)| {
+                (ox..ox + FINDER_SIZE).contains(&mx) && (oy..oy + FINDER_SIZE).contains(&my)
+            }) {
+                self.eye_contains(fx - ox as f64, fy - oy as f64)
+            } else if colors[my * width + mx] == Color::Dark {
+                let (x, y) = (mx as isize, my as isize);
+                let neighbours = [
+                    is_dark(x, y - 1),
+                    is_dark(x + 1, y),
+                    is_dark(x, y + 1),
+                    is_dark(x - 1, y),
+                ];
+                self.module_contains(fx.fract(), fy.fract(), neighbours)
+            } else {
+                false
+            };
+
+            if dark {
+                self.foreground
+            } else {
+                self.background
+            }
+        });
+
+        Ok(image)
+    }
+
+    /// Whether a point inside a dark module's cell is covered by its shape
+    ///
+    /// `neighbours` holds the dark state of the top, right, bottom and left
+    /// modules.
+    fn module_contains(&self, u: f64, v: f64, neighbours: [bool; 4]) -> bool {
+        match self.module_shape {
+            ModuleShape::Square => true,
+            ModuleShape::Dot => (u - 0.5).powi(2) + (v - 0.5).powi(2) <= 0.25,
+            ModuleShape::Rounded => {
+                let [top, right, bottom, left] = neighbours;
+                // Only round corners that are not shared with a neighbour
+                let rounded = match (u < 0.5, v < 0.5) {
+                    (true, true) => !top && !left,
+                    (false, true) => !top && !right,
+                    (false, false) => !bottom && !right,
+                    (true, false) => !bottom && !left,
+                };
+                !rounded || in_rounded_rect(u, v, (0.0, 0.0), (1.0, 1.0), 0.5)
+            }
+        }
+    }
+
+    /// Whether a point inside a finder pattern, in module units relative to
+    /// its top-left corner, is dark
+    fn eye_contains(&self, x: f64, y: f64) -> bool {
+        let size = FINDER_SIZE as f64;
+        match self.eye_style {
+            EyeStyle::Square | EyeStyle::Rounded => {
+                let (outer, inner, pupil) = if self.eye_style == EyeStyle::Rounded {
+                    (2.0, 1.0, 1.0)
+                } else {
+                    (0.0, 0.0, 0.0)
+                };
+                let frame = in_rounded_rect(x, y, (0.0, 0.0), (size, size), outer)
+                    && !in_rounded_rect(x, y, (1.0, 1.0), (size - 1.0, size - 1.0), inner);
+                frame || in_rounded_rect(x, y, (2.0, 2.0), (size - 2.0, size - 2.0), pupil)
+            }
+            EyeStyle::Circle => {
+                let centre = size / 2.0;
+                let distance = ((x - centre).powi(2) + (y - centre).powi(2)).sqrt();
+                (centre - 1.0..=centre).contains(&distance) || distance <= 1.5
+            }
+        }
+    }
+}
+
+/// Point-in-rectangle test with corner radius `r`
+fn in_rounded_rect(x: f64, y: f64, min: (f64, f64), max: (f64, f64), r: f64) -> bool {
+    if x < min.0 || y < min.1 || x >= max.0 || y >= max.1 {
+        return false;
+    }
+
+    // Distance from the point to the inner rectangle inset by the radius
+    let dx = (min.0 + r - x).max(x - (max.0 - r)).max(0.0);
+    let dy = (min.1 + r - y).max(y - (max.1 - r)).max(0.0);
+    dx * dx + dy * dy <= r * r
+}
+
+/// Blend a colour onto an opaque backdrop
+fn composite(color: Rgba<u8>, backdrop: [f64; 3]) -> [f64; 3] {
+    let alpha = f64::from(color[3]) / 255.0;
+    [0, 1, 2].map(|i| f64::from(color[i]) * alpha + backdrop[i] * (1.0 - alpha))
+}
+
+/// WCAG relative luminance of an sRGB colour
+fn luminance(rgb: [f64; 3]) -> f64 {
+    let [r, g, b] = rgb.map(|channel| {
+        let c = channel / 255.0;
+        if c <= 0.039_28 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> QrCode {
+        QrCode::new(b"ethqr").unwrap()
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        let style = QRStyle::new();
+        assert!((style.contrast_ratio() - 21.0).abs() < 1e-9);
+
+        let style = QRStyle::new()
+            .foreground(Rgba([0x77, 0x77, 0x77, 0xff]))
+            .background(Rgba([0xff, 0xff, 0xff, 0xff]));
+        assert!(style.contrast_ratio() > 4.4 && style.contrast_ratio() < 4.5);
+        assert!(matches!(
+            style.validate(),
+            Err(QRError::InsufficientContrast { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_inverted_and_translucent_colors() {
+        let inverted = QRStyle::new()
+            .foreground(Rgba([255, 255, 255, 255]))
+            .background(Rgba([0, 0, 0, 255]));
+        assert!(matches!(
+            inverted.validate(),
+            Err(QRError::ValidationError { .. })
+        ));
+
+        let faded = QRStyle::new().foreground(Rgba([0, 0, 0, 40]));
+        assert!(faded.validate().is_err());
+    }
+
+    #[test]
+    fn test_render_dimensions_and_colors() {
+        let qr_code = sample();
+        let fg = Rgba([0x1a, 0x23, 0x7e, 0xff]);
+        let bg = Rgba([0xff, 0xf8, 0xe1, 0xff]);
+        let style = QRStyle::new()
+            .foreground(fg)
+            .background(bg)
+            .module_size(4)
+            .quiet_zone(2);
+
+        let image = style.render(&qr_code).unwrap();
+        let size = (qr_code.width() as u32 + 4) * 4;
+        assert_eq!(image.dimensions(), (size, size));
+
+        // Quiet zone, then the top-left corner of the finder pattern
+        assert_eq!(*image.get_pixel(0, 0), bg);
+        assert_eq!(*image.get_pixel(8, 8), fg);
+    }
+
+    #[test]
+    fn test_shapes_are_lighter_than_squares() {
+        let qr_code = sample();
+        let dark_pixels = |style: QRStyle| {
+            style
+                .render(&qr_code)
+                .unwrap()
+                .pixels()
+                .filter(|p| p[0] == 0)
+                .count()
+        };
+
+        let square = dark_pixels(QRStyle::new());
+        let dots = dark_pixels(QRStyle::new().module_shape(ModuleShape::Dot));
+        let rounded = dark_pixels(QRStyle::new().module_shape(ModuleShape::Rounded));
+        let circle_eyes = dark_pixels(QRStyle::new().eye_style(EyeStyle::Circle));
+
+        assert!(dots < rounded && rounded < square);
+        assert!(circle_eyes < square);
+    }
+}
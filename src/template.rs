@@ -0,0 +1,359 @@
+//! Dynamic QR codes stamped from a pre-encoded merchant template
+//!
+//! A checkout usually changes only the amount, bill number and reference
+//! label of an otherwise fixed merchant code. [`QRTemplate`] validates the
+//! merchant's fields once and keeps them encoded, together with the CRC state
+//! after the leading fields, so each [`Stamp`] only encodes and checksums
+//! what differs. Stamped payloads are byte-for-byte what
+//! [`QRBuilder::build_payload`] produces for the same data.
+//!
+//! ```
+//! use ethqr_gen::{QRBuilder, constants, fields::SchemeConfig, template::QRTemplate};
+//!
+//! let merchant = QRBuilder::new()
+//!     .merchant_name("Coffee Shop")
+//!     .merchant_city("Addis Ababa")
+//!     .merchant_category_code("5812")
+//!     .add_scheme(SchemeConfig::visa("4111111111111111"));
+//! let template = QRTemplate::new(&merchant)?;
+//!
+//! let mut buf = [0u8; constants::MAX_QR_LENGTH];
+//! let payload = template.stamp("120.50").bill_number("INV-001").encode_into(&mut buf)?;
+//! assert!(ethqr_gen::parser::parse(payload)?.is_dynamic());
+//! # Ok::<(), ethqr_gen::error::QRError>(())
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::QRBuilder;
+use crate::constants;
+use crate::crc::Crc16;
+use crate::encoder::PayloadWriter;
+use crate::error::{QRError, Result};
+use crate::fields::AdditionalData;
+use crate::tags;
+use crate::validation::{self, ValidationReport};
+
+/// Index of the bill number in [`AdditionalData::sub_tags`]
+const BILL_NUMBER: usize = 0;
+
+/// Index of the reference label in [`AdditionalData::sub_tags`]
+const REFERENCE_LABEL: usize = 4;
+
+/// Pre-validated, pre-encoded merchant fields for stamping dynamic QR codes
+#[derive(Debug, Clone, PartialEq)]
+pub struct QRTemplate {
+    /// Tags 00 to 53
+    head: String,
+    /// CRC state after `head`
+    head_crc: Crc16<'static>,
+    /// Tags 58 to 60
+    merchant: String,
+    /// Additional data shared by every stamp
+    additional_data: AdditionalData,
+    /// Tags after 62
+    tail: String,
+}
+
+impl QRTemplate {
+    /// Validate and encode the fixed fields of `builder`
+    ///
+    /// Any transaction amount on `builder` is ignored, since each stamp
+    /// supplies its own. Stamped codes are dynamic unless `builder` sets a
+    /// static point of initiation. Bill numbers and reference labels on
+    /// `builder` are used unless a stamp overrides them.
+    ///
+    /// # Errors
+    /// Returns an error if the builder fails validation or its fixed fields
+    /// alone exceed the maximum QR length.
+    pub fn new(builder: &QRBuilder) -> Result<Self> {
+        // Every stamp has an amount
+        builder.validate_fields()?;
+        builder.validate_mode(true)?;
+
+        let mut buf = [0u8; constants::MAX_QR_LENGTH];
+        let mut writer = PayloadWriter::new(&mut buf);
+        builder.write_head(&mut writer, builder.resolve_point_of_initiation(true))?;
+        let head_end = writer.len();
+        let head_crc = writer.crc();
+        builder.write_merchant(&mut writer)?;
+        let merchant_end = writer.len();
+        builder.write_tail(&mut writer)?;
+
+        if writer.len() > constants::MAX_QR_LENGTH {
+            return Err(QRError::PayloadTooLong {
+                length: writer.len(),
+            });
+        }
+
+        // Only whole `&str`s are ever written, and the split points are tag
+        // boundaries
+        let encoded =
+            core::str::from_utf8(writer.as_bytes()).map_err(|e| QRError::BuilderError {
+                message: format!("Failed to build QR template: {e}"),
+            })?;
+
+        Ok(Self {
+            head: encoded[..head_end].to_string(),
+            head_crc,
+            merchant: encoded[head_end..merchant_end].to_string(),
+            additional_data: builder.additional_data.clone().unwrap_or_default(),
+            tail: encoded[merchant_end..].to_string(),
+        })
+    }
+
+    /// Start a dynamic QR code for `amount`
+    pub fn stamp<'a>(&'a self, amount: &'a str) -> Stamp<'a> {
+        Stamp {
+            template: self,
+            amount,
+            bill_number: None,
+            reference_label: None,
+        }
+    }
+}
+
+/// A dynamic QR code being stamped from a [`QRTemplate`]
+#[derive(Debug, Clone, Copy)]
+#[must_use]
+pub struct Stamp<'a> {
+    template: &'a QRTemplate,
+    amount: &'a str,
+    bill_number: Option<&'a str>,
+    reference_label: Option<&'a str>,
+}
+
+impl<'a> Stamp<'a> {
+    /// Set the bill number (tag 62 sub-tag 01)
+    pub fn bill_number(mut self, bill_number: &'a str) -> Self {
+        self.bill_number = Some(bill_number);
+        self
+    }
+
+    /// Set the reference label (tag 62 sub-tag 05)
+    pub fn reference_label(mut self, reference_label: &'a str) -> Self {
+        self.reference_label = Some(reference_label);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        // Stricter than the builder: stamped codes are shown without review,
+        // so amounts the validator warns about are rejected too
+        let mut report = ValidationReport::default();
+        validation::check_amount(self.amount, None, &mut report);
+        if !report.issues.is_empty() {
+            return Err(QRError::InvalidValue {
+                field: "transaction_amount".to_string(),
+                value: self.amount.to_string(),
+            });
+        }
+
+        if let Some(label) = self.reference_label
+            && label.len() > constants::MAX_REFERENCE_LABEL_LEN
+        {
+            return Err(QRError::ValueTooLong {
+                field: "reference_label".to_string(),
+                length: label.len(),
+                max_length: constants::MAX_REFERENCE_LABEL_LEN,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Encode the payload into `buf` without allocating, returning the
+    /// written prefix of `buf`
+    ///
+    /// # Errors
+    /// Returns an error if the amount is malformed, not positive or has more
+    /// than two decimal places, the reference label is too long, the payload
+    /// exceeds the maximum QR length or it does not fit in `buf`.
+    pub fn encode_into<'b>(&self, buf: &'b mut [u8]) -> Result<&'b str> {
+        self.validate()?;
+
+        let template = self.template;
+        let mut writer = PayloadWriter::resume(buf, template.head.as_bytes(), template.head_crc);
+        writer.tag(tags::TRANSACTION_AMOUNT, self.amount)?;
+        writer.encoded(&template.merchant);
+
+        let mut additional_data = template.additional_data.sub_tags();
+        if self.bill_number.is_some() {
+            additional_data[BILL_NUMBER].1 = self.bill_number;
+        }
+        if self.reference_label.is_some() {
+            additional_data[REFERENCE_LABEL].1 = self.reference_label;
+        }
+        writer.template(tags::ADDITIONAL_DATA, &additional_data)?;
+
+        writer.encoded(&template.tail);
+        writer.finish()
+    }
+
+    /// Build the encoded payload string, including the trailing CRC
+    ///
+    /// # Errors
+    /// Returns the errors of [`Stamp::encode_into`].
+    pub fn build_payload(&self) -> Result<String> {
+        let mut buf = [0u8; constants::MAX_QR_LENGTH];
+        Ok(self.encode_into(&mut buf)?.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fields::{LanguageTemplate, PointOfInitiation, SchemeConfig, Validity};
+
+    fn merchant() -> QRBuilder {
+        QRBuilder::new()
+            .merchant_name("Restaurant")
+            .merchant_city("Dire Dawa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::ips_et(
+                "581b314e257f41bfbbdc6384daa31d16",
+                "CBETETAA",
+                "10000171234567890",
+            ))
+            .add_scheme(SchemeConfig::visa("4111111111111111"))
+    }
+
+    #[test]
+    fn test_matches_builder() {
+        let builder = merchant()
+            .additional_data(AdditionalData::new().terminal_number("POS-07"))
+            .language_template(LanguageTemplate::new("am", "ምግብ ቤት"))
+            .validity(Validity::until(1_760_000_600));
+        let template = QRTemplate::new(&builder).unwrap();
+
+        let stamped = template
+            .stamp("50.00")
+            .bill_number("INV-001")
+            .reference_label("ORDER-123")
+            .build_payload()
+            .unwrap();
+        let built = builder
+            .clone()
+            .transaction_amount("50.00")
+            .additional_data(
+                AdditionalData::new()
+                    .bill_number("INV-001")
+                    .reference_label("ORDER-123")
+                    .terminal_number("POS-07"),
+            )
+            .build_payload()
+            .unwrap();
+        assert_eq!(stamped, built);
+
+        // Without stamped additional data the template's own is kept
+        assert_eq!(
+            template.stamp("7").build_payload().unwrap(),
+            builder.transaction_amount("7").build_payload().unwrap()
+        );
+
+        let fixed_price = merchant().point_of_initiation(PointOfInitiation::Static);
+        let stamped = QRTemplate::new(&fixed_price)
+            .unwrap()
+            .stamp("30.00")
+            .build_payload()
+            .unwrap();
+        assert!(stamped.starts_with("000201010211"));
+        assert_eq!(
+            stamped,
+            fixed_price
+                .transaction_amount("30.00")
+                .build_payload()
+                .unwrap()
+        );
+
+        let plain = QRTemplate::new(&merchant()).unwrap();
+        assert_eq!(
+            plain.stamp("12.5").build_payload().unwrap(),
+            merchant()
+                .transaction_amount("12.5")
+                .build_payload()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_stamps() {
+        let template = QRTemplate::new(&merchant()).unwrap();
+
+        for amount in ["", ".", "1.2.3", "-5", "12345678901234", "0.00", "1.005"] {
+            assert!(
+                matches!(
+                    template.stamp(amount).build_payload(),
+                    Err(QRError::InvalidValue { .. })
+                ),
+                "{amount:?}"
+            );
+        }
+        assert!(matches!(
+            template
+                .stamp("5")
+                .reference_label(&"R".repeat(26))
+                .build_payload(),
+            Err(QRError::ValueTooLong { .. })
+        ));
+
+        let mut small = [0u8; 32];
+        assert!(matches!(
+            template.stamp("5").encode_into(&mut small),
+            Err(QRError::BuilderError { .. })
+        ));
+
+        assert!(matches!(
+            QRTemplate::new(&merchant().merchant_category_code("58")),
+            Err(QRError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            QRTemplate::new(
+                &merchant()
+                    .point_of_initiation(PointOfInitiation::Static)
+                    .validity(Validity::until(1_760_000_600))
+            ),
+            Err(QRError::ValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_amounts_agree_with_validator() {
+        let template = QRTemplate::new(&merchant()).unwrap();
+
+        for amount in [
+            "120.50",
+            "7",
+            "0.5",
+            ".5",
+            "5.",
+            "",
+            "0",
+            "0.00",
+            "-5",
+            "1,000",
+            "1.005",
+            "1e3",
+            "1234567890.12",
+            "12345678901234",
+        ] {
+            let mut report = ValidationReport::default();
+            validation::check_amount(amount, None, &mut report);
+
+            let stamped = template.stamp(amount).build_payload();
+            assert_eq!(stamped.is_ok(), report.issues.is_empty(), "{amount:?}");
+            if let Ok(payload) = stamped {
+                let built = merchant().transaction_amount(amount).build_payload();
+                assert_eq!(payload, built.unwrap());
+                let report = validation::validate(&payload);
+                assert!(
+                    !report
+                        .issues
+                        .iter()
+                        .any(|issue| issue.tag.as_deref() == Some(tags::TRANSACTION_AMOUNT)),
+                    "{amount:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Terminal rendering of QR codes
+//!
+//! Renders a [`QrCode`] as text so it can be inspected directly in a terminal
+//! or over SSH without writing image files.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::{QRBuilder, fields::SchemeConfig};
+//! use ethqr_gen::terminal::{TerminalRenderer, TerminalStyle};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let qr_code = QRBuilder::new()
+//!     .merchant_name("Coffee Shop")
+//!     .merchant_city("Addis Ababa")
+//!     .merchant_category_code("5812")
+//!     .add_scheme(SchemeConfig::visa("4111111111111111"))
+//!     .build()?;
+//!
+//! let text = TerminalRenderer::new()
+//!     .style(TerminalStyle::HalfBlock)
+//!     .invert(true) // dark terminal background
+//!     .render(&qr_code);
+//! println!("{text}");
+//! # Ok(())
+//! # }
+//! ```
+
+use qrcode::{Color, QrCode};
+
+/// Width of the quiet zone in modules, as required by the QR code specification
+pub const QUIET_ZONE_MODULES: usize = 4;
+
+const ANSI_DARK: &str = "\x1b[40m";
+const ANSI_LIGHT: &str = "\x1b[47m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Output style for terminal rendering
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalStyle {
+    /// Unicode half-block characters, two modules per character cell
+    #[default]
+    HalfBlock,
+    /// ANSI background colours, one module per two character cells
+    Ansi,
+}
+
+/// Renderer producing a textual representation of a QR code
+#[derive(Debug, Clone)]
+pub struct TerminalRenderer {
+    style: TerminalStyle,
+    quiet_zone: bool,
+    invert: bool,
+}
+
+impl Default for TerminalRenderer {
+    fn default() -> Self {
+        Self {
+            style: TerminalStyle::default(),
+            quiet_zone: true,
+            invert: false,
+        }
+    }
+}
+
+impl TerminalRenderer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the output style
+    pub fn style(mut self, style: TerminalStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Surround the code with a quiet zone (enabled by default)
+    pub fn quiet_zone(mut self, quiet_zone: bool) -> Self {
+        self.quiet_zone = quiet_zone;
+        self
+    }
+
+    /// Swap dark and light modules
+    ///
+    /// Half-block output draws dark modules with the foreground colour, which
+    /// is only scannable on light backgrounds. Enable this for terminals with a
+    /// dark background.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Render the QR code as a string, one terminal line per output row
+    #[must_use]
+    pub fn render(&self, qr_code: &QrCode) -> String {
+        let width = qr_code.width();
+        let margin = if self.quiet_zone {
+            QUIET_ZONE_MODULES
+        } else {
+            0
+        };
+        let size = width + 2 * margin;
+
+        // Quiet zone modules are always light
+        let is_dark = |x: usize, y: usize| -> bool {
+            let dark = x >= margin
+                && y >= margin
+                && x < margin + width
+                && y < margin + width
+                && qr_code[(x - margin, y - margin)] == Color::Dark;
+            dark != self.invert
+        };
+
+        let mut output = String::new();
+
+        match self.style {
+            TerminalStyle::HalfBlock => {
+                for y in (0..size).step_by(2) {
+                    for x in 0..size {
+                        let top = is_dark(x, y);
+                        // An odd number of rows leaves the last bottom half light
+                        let bottom = y + 1 < size && is_dark(x, y + 1);
+                        output.push(match (top, bottom) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        });
+                    }
+                    output.push('\n');
+                }
+            }
+            TerminalStyle::Ansi => {
+                for y in 0..size {
+                    let mut current = None;
+                    for x in 0..size {
+                        let dark = is_dark(x, y);
+                        if current != Some(dark) {
+                            output.push_str(if dark { ANSI_DARK } else { ANSI_LIGHT });
+                            current = Some(dark);
+                        }
+                        output.push_str("  ");
+                    }
+                    output.push_str(ANSI_RESET);
+                    output.push('\n');
+                }
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> QrCode {
+        QrCode::new(b"ethqr").unwrap()
+    }
+
+    #[test]
+    fn test_half_block_dimensions() {
+        let qr_code = sample();
+        let size = qr_code.width() + 2 * QUIET_ZONE_MODULES;
+        let output = TerminalRenderer::new().render(&qr_code);
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), size.div_ceil(2));
+        assert!(lines.iter().all(|line| line.chars().count() == size));
+
+        // Quiet zone rows are blank
+        assert!(lines[0].chars().all(|c| c == ' '));
+    }
+
+    #[test]
+    fn test_half_block_without_quiet_zone() {
+        let qr_code = sample();
+        let output = TerminalRenderer::new().quiet_zone(false).render(&qr_code);
+
+        // Top-left finder pattern: a dark row above a hollow one
+        assert!(output.starts_with("█▀▀▀▀▀█"));
+    }
+
+    #[test]
+    fn test_invert_swaps_modules() {
+        let qr_code = sample();
+        let normal = TerminalRenderer::new().render(&qr_code);
+        let inverted = TerminalRenderer::new().invert(true).render(&qr_code);
+
+        // The last line of an odd-sized code has an unused bottom half
+        let normal_lines: Vec<&str> = normal.lines().collect();
+        let inverted_lines: Vec<&str> = inverted.lines().collect();
+        let rows = normal_lines.len() - 1;
+
+        let normal = normal_lines[..rows].concat();
+        let inverted = inverted_lines[..rows].concat();
+        for (a, b) in normal.chars().zip(inverted.chars()) {
+            let expected = match a {
+                '█' => ' ',
+                ' ' => '█',
+                '▀' => '▄',
+                '▄' => '▀',
+                other => other,
+            };
+            assert_eq!(b, expected);
+        }
+    }
+
+    #[test]
+    fn test_ansi_output() {
+        let qr_code = sample();
+        let size = qr_code.width() + 2 * QUIET_ZONE_MODULES;
+        let output = TerminalRenderer::new()
+            .style(TerminalStyle::Ansi)
+            .render(&qr_code);
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), size);
+        assert!(lines.iter().all(|line| line.ends_with(ANSI_RESET)));
+        assert!(output.contains(ANSI_DARK));
+    }
+}
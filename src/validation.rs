@@ -0,0 +1,482 @@
+//! Validation of QR payloads
+//!
+//! Unlike [`parser::parse`](crate::parser::parse), which stops at the first
+//! problem, validation inspects the whole payload and reports every issue it
+//! finds, graded by severity.
+//!
+//! # Examples
+//!
+//! ```
+//! use ethqr_gen::validation::validate;
+//!
+//! let report = validate("000201010212520458125303230");
+//! assert!(!report.is_valid());
+//! for issue in report.errors() {
+//!     println!("{issue}");
+//! }
+//! ```
+
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::crc::CrcError;
+use crate::parser::{self, parse_tlv};
+use crate::{EMVTag, constants, crc, tags};
+
+/// Maximum length of the transaction amount (tag 54)
+const MAX_AMOUNT_LEN: usize = 13;
+
+/// Tags that every payload must contain
+const MANDATORY_TAGS: [(&str, &str); 7] = [
+    (tags::PAYLOAD_FORMAT_INDICATOR, "payload format indicator"),
+    (tags::MERCHANT_CATEGORY_CODE, "merchant category code"),
+    (tags::TRANSACTION_CURRENCY, "transaction currency"),
+    (tags::COUNTRY_CODE, "country code"),
+    (tags::MERCHANT_NAME, "merchant name"),
+    (tags::MERCHANT_CITY, "merchant city"),
+    (tags::CRC, "CRC"),
+];
+
+/// How serious a validation issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    /// The payload violates the standard and may be rejected by wallets
+    Error,
+    /// The payload is valid but unusual
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A single problem found in a payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Issue {
+    pub severity: Severity,
+    /// Root tag the issue relates to, if any
+    pub tag: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tag {
+            Some(ref tag) => write!(f, "{} [tag {tag}]: {}", self.severity, self.message),
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
+
+/// Result of validating a payload
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValidationReport {
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Whether the payload has no errors (warnings are allowed)
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Issues with [`Severity::Error`]
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// Issues with [`Severity::Warning`]
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn error(&mut self, tag: Option<&str>, message: impl Into<String>) {
+        self.push(Severity::Error, tag, message);
+    }
+
+    fn warning(&mut self, tag: Option<&str>, message: impl Into<String>) {
+        self.push(Severity::Warning, tag, message);
+    }
+
+    fn push(&mut self, severity: Severity, tag: Option<&str>, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity,
+            tag: tag.map(str::to_string),
+            message: message.into(),
+        });
+    }
+}
+
+/// Validate a payload and collect every issue found
+#[must_use]
+pub fn validate(payload: &str) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    if payload.len() > constants::MAX_QR_LENGTH {
+        report.error(
+            None,
+            format!(
+                "payload is {} bytes, maximum is {}",
+                payload.len(),
+                constants::MAX_QR_LENGTH
+            ),
+        );
+    }
+
+    let root = match parse_tlv(payload) {
+        Ok(root) => root,
+        Err(e) => {
+            report.error(None, e.to_string());
+            return report;
+        }
+    };
+
+    check_structure(&root, &mut report);
+
+    // Structural CRC problems are reported by the tag checks
+    if let Err(e @ CrcError::Mismatch { .. }) = crc::check_crc(payload) {
+        report.error(Some(tags::CRC), e.to_string());
+    }
+
+    for tag in &root {
+        check_tag(tag, &root, &mut report);
+    }
+
+    report
+}
+
+fn check_structure(root: &[EMVTag], report: &mut ValidationReport) {
+    let mut seen = BTreeSet::new();
+    let mut previous: Option<&str> = None;
+
+    for tag in root {
+        if !tag.id.bytes().all(|b| b.is_ascii_digit()) {
+            report.error(Some(&tag.id), "tag ID must be two digits");
+        }
+        if !seen.insert(tag.id.as_str()) {
+            report.error(Some(&tag.id), "duplicate tag");
+        }
+        if let Some(previous) = previous
+            && tag.id.as_str() < previous
+            && tag.id != tags::CRC
+        {
+            report.warning(Some(&tag.id), format!("tag appears after tag {previous}"));
+        }
+        previous = Some(&tag.id);
+    }
+
+    for (id, name) in MANDATORY_TAGS {
+        if !seen.contains(id) {
+            report.error(Some(id), format!("missing mandatory {name}"));
+        }
+    }
+
+    if !root.iter().any(|tag| is_merchant_account(&tag.id)) {
+        report.error(None, "no merchant account information (tags 02-51)");
+    }
+
+    if root
+        .first()
+        .is_some_and(|tag| tag.id != tags::PAYLOAD_FORMAT_INDICATOR)
+    {
+        report.error(
+            Some(tags::PAYLOAD_FORMAT_INDICATOR),
+            "payload format indicator must be the first tag",
+        );
+    }
+
+    if root.last().is_some_and(|tag| tag.id != tags::CRC) && seen.contains(tags::CRC) {
+        report.error(Some(tags::CRC), "CRC must be the last tag");
+    }
+}
+
+fn check_tag(tag: &EMVTag, root: &[EMVTag], report: &mut ValidationReport) {
+    let id = Some(tag.id.as_str());
+    let value = tag.value.as_str();
+    let has_amount = root.iter().any(|t| t.id == tags::TRANSACTION_AMOUNT);
+    let has_end_to_end_id = root.iter().any(|t| t.id == tags::END_TO_END_ID);
+
+    match tag.id.as_str() {
+        tags::PAYLOAD_FORMAT_INDICATOR if value != constants::PAYLOAD_FORMAT_INDICATOR => {
+            report.error(id, format!("unsupported payload format '{value}'"));
+        }
+        tags::POINT_OF_INITIATION => match value {
+            constants::STATIC_QR_POI if has_end_to_end_id => {
+                report.error(id, "static QR code carries an end-to-end ID");
+            }
+            constants::DYNAMIC_QR_POI if !has_amount && !has_end_to_end_id => {
+                report.error(
+                    id,
+                    "dynamic QR code has neither a transaction amount nor an end-to-end ID",
+                );
+            }
+            constants::STATIC_QR_POI | constants::DYNAMIC_QR_POI => {}
+            _ => report.error(id, format!("invalid point of initiation '{value}'")),
+        },
+        tags::IPS_ET => {
+            if let Some(message) = ips_et_error(value) {
+                report.error(id, message);
+            }
+        }
+        tags::MERCHANT_CATEGORY_CODE
+            if value.len() != 4 || !value.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            report.error(
+                id,
+                format!("merchant category code '{value}' is not 4 digits"),
+            );
+        }
+        tags::TRANSACTION_CURRENCY => {
+            if value.len() != 3 || !value.bytes().all(|b| b.is_ascii_digit()) {
+                report.error(
+                    id,
+                    format!("currency '{value}' is not a numeric ISO 4217 code"),
+                );
+            } else if value != constants::ETB_CURRENCY_CODE {
+                report.warning(id, format!("currency '{value}' is not ETB"));
+            }
+        }
+        tags::TRANSACTION_AMOUNT => check_amount(value, id, report),
+        tags::COUNTRY_CODE => {
+            if value.len() != 2 || !value.bytes().all(|b| b.is_ascii_uppercase()) {
+                report.error(
+                    id,
+                    format!("country code '{value}' is not ISO 3166-1 alpha-2"),
+                );
+            } else if value != constants::ETHIOPIA_COUNTRY_CODE {
+                report.warning(id, format!("country code '{value}' is not ET"));
+            }
+        }
+        tags::MERCHANT_NAME => {
+            check_length(value, constants::MAX_MERCHANT_NAME_LEN, id, report);
+        }
+        tags::MERCHANT_CITY => {
+            check_length(value, constants::MAX_MERCHANT_CITY_LEN, id, report);
+        }
+        tags::VALIDITY => match parser::parse_validity(value) {
+            Ok(Some(validity)) => {
+                if validity.not_before >= Some(validity.expires_at) {
+                    report.error(id, "validity window ends before it starts");
+                }
+                if root.iter().any(|t| {
+                    t.id == tags::POINT_OF_INITIATION && t.value == constants::STATIC_QR_POI
+                }) {
+                    report.error(id, "static QR code carries a validity window");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => report.error(id, format!("malformed validity window: {e}")),
+        },
+        tags::ADDITIONAL_DATA => {
+            if let Err(e) = parse_tlv(value) {
+                report.error(id, format!("malformed additional data: {e}"));
+            }
+        }
+        tags::CRC if value.len() != 4 || !value.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            report.error(id, format!("CRC '{value}' is not 4 hex digits"));
+        }
+        _ => {}
+    }
+}
+
+/// Report problems with a transaction amount (tag 54)
+///
+/// Also used by [`crate::template::Stamp`], which rejects any issue.
+pub(crate) fn check_amount(value: &str, id: Option<&str>, report: &mut ValidationReport) {
+    if !value.bytes().any(|b| b.is_ascii_digit())
+        || value.len() > MAX_AMOUNT_LEN
+        || value.bytes().filter(|&b| b == b'.').count() > 1
+        || !value.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+    {
+        report.error(id, format!("invalid transaction amount '{value}'"));
+        return;
+    }
+
+    if value.parse::<f64>().is_ok_and(|amount| amount <= 0.0) {
+        report.error(id, "transaction amount must be positive");
+    }
+    if value
+        .split_once('.')
+        .is_some_and(|(_, decimals)| decimals.len() > 2)
+    {
+        report.warning(id, "transaction amount has more than two decimal places");
+    }
+}
+
+fn check_length(value: &str, max_length: usize, id: Option<&str>, report: &mut ValidationReport) {
+    if value.is_empty() {
+        report.error(id, "value is empty");
+    } else if value.len() > max_length {
+        report.error(
+            id,
+            format!(
+                "value is {} characters, maximum is {max_length}",
+                value.len()
+            ),
+        );
+    }
+}
+
+fn ips_et_error(value: &str) -> Option<String> {
+    match parser::parse_ips_et(value) {
+        Ok(scheme) => scheme.encode().err().map(|e| e.to_string()),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+fn is_merchant_account(id: &str) -> bool {
+    id.parse::<u8>().is_ok_and(|n| (2..=51).contains(&n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QRBuilder;
+    use crate::fields::{SchemeConfig, Validity};
+
+    fn payload() -> String {
+        QRBuilder::new()
+            .merchant_name("Restaurant")
+            .merchant_city("Dire Dawa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::ips_et(
+                "581b314e257f41bfbbdc6384daa31d16",
+                "CBETETAA",
+                "10000171234567890",
+            ))
+            .transaction_amount("50.00")
+            .build_payload()
+            .unwrap()
+    }
+
+    /// Rebuild a payload from tags with a correct CRC
+    fn with_crc(tags: &[EMVTag]) -> String {
+        let mut payload = tags.iter().map(EMVTag::encode).collect::<String>();
+        payload.push_str("6304");
+
+        let mut crc = crc::Crc16::new();
+        crc.update(payload.as_bytes());
+        payload.push_str(core::str::from_utf8(&crc.to_hex()).unwrap());
+        payload
+    }
+
+    #[test]
+    fn test_valid_payload() {
+        let report = validate(&payload());
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+    }
+
+    #[test]
+    fn test_reports_multiple_issues() {
+        let mut tags = parse_tlv(&payload()).unwrap();
+        tags.pop();
+        for tag in &mut tags {
+            match tag.id.as_str() {
+                "52" => tag.value = "58X2".to_string(),
+                "54" => tag.value = "50.005".to_string(),
+                "58" => tag.value = "KE".to_string(),
+                _ => {}
+            }
+        }
+
+        let report = validate(&with_crc(&tags));
+        assert!(!report.is_valid());
+        assert_eq!(report.errors().count(), 1);
+        assert_eq!(report.warnings().count(), 2);
+        assert_eq!(
+            report.errors().next().unwrap().tag.as_deref(),
+            Some(tags::MERCHANT_CATEGORY_CODE)
+        );
+    }
+
+    #[test]
+    fn test_missing_tags_and_bad_crc() {
+        let report = validate("000201010212520458125303230");
+        let missing: Vec<_> = report
+            .errors()
+            .filter_map(|issue| issue.tag.as_deref())
+            .collect();
+
+        assert!(missing.contains(&tags::MERCHANT_NAME));
+        assert!(missing.contains(&tags::CRC));
+        assert!(
+            report
+                .errors()
+                .any(|issue| issue.message.contains("merchant account"))
+        );
+    }
+
+    #[test]
+    fn test_validity_on_static_code() {
+        let payload = QRBuilder::new()
+            .merchant_name("Restaurant")
+            .merchant_city("Dire Dawa")
+            .merchant_category_code("5812")
+            .add_scheme(SchemeConfig::visa("4111111111111111"))
+            .transaction_amount("50.00")
+            .validity(Validity::for_duration(1_760_000_000, 600))
+            .build_payload()
+            .unwrap();
+        assert!(validate(&payload).issues.is_empty());
+
+        let mut tags = parse_tlv(&payload).unwrap();
+        tags.pop();
+        tags.retain(|tag| tag.id != tags::TRANSACTION_AMOUNT);
+        for tag in &mut tags {
+            if tag.id == tags::POINT_OF_INITIATION {
+                tag.value = constants::STATIC_QR_POI.to_string();
+            }
+        }
+
+        let report = validate(&with_crc(&tags));
+        assert!(report.errors().any(|issue| {
+            issue.tag.as_deref() == Some(tags::VALIDITY) && issue.message.contains("static")
+        }));
+    }
+
+    #[test]
+    fn test_invalid_ips_et() {
+        let mut tags = parse_tlv(&payload()).unwrap();
+        tags.pop();
+        tags[2].value = "0004abcd0108CBETETAA020212".to_string();
+
+        let report = validate(&with_crc(&tags));
+        assert!(report.errors().any(|issue| {
+            issue.tag.as_deref() == Some(tags::IPS_ET) && issue.message.contains("guid")
+        }));
+    }
+
+    #[test]
+    fn test_dynamic_without_amount_is_an_error() {
+        // What the builder refuses to encode: dynamic, no amount, no tag 84
+        let mut tags = parse_tlv(&payload()).unwrap();
+        tags.retain(|tag| tag.id != tags::TRANSACTION_AMOUNT && tag.id != tags::CRC);
+        let report = validate(&with_crc(&tags));
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.errors().next().unwrap().tag.as_deref(),
+            Some(tags::POINT_OF_INITIATION)
+        );
+    }
+}
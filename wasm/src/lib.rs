@@ -0,0 +1,218 @@
+//! WebAssembly bindings for `ethqr-gen`
+//!
+//! Build a browser package with `wasm-pack build wasm --target web`, or run
+//! the tests headlessly with
+//! `cargo test -p ethqr-wasm --target wasm32-unknown-unknown`.
+//!
+//! Plain objects passed to and returned from these functions use the serde
+//! field names of the core crate (`merchant_name`, `schemes`, ...), with
+//! schemes tagged by `scheme`:
+//!
+//! ```js
+//! import { QRBuilder, parse, validate } from "ethqr-wasm";
+//!
+//! const builder = new QRBuilder({
+//!   merchant_name: "Coffee Shop",
+//!   merchant_city: "Addis Ababa",
+//!   merchant_category_code: "5812",
+//!   schemes: [{ scheme: "visa", account_info: "4111111111111111" }],
+//! });
+//! builder.setTransactionAmount("25.00");
+//!
+//! const payload = builder.buildPayload();
+//! const svg = builder.buildSvg(256);
+//! const report = validate(payload); // { valid: true, issues: [] }
+//! ```
+//!
+//! Errors are thrown as `Error` objects whose `code` property comes from
+//! `QRError::code`.
+
+use ethqr_gen::error::QRError;
+use ethqr_gen::fields::{AdditionalData, LanguageTemplate, SchemeConfig};
+use ethqr_gen::validation::Issue;
+use ethqr_gen::{QRBuilder, constants, crc, parser, validation};
+use qrcode::QrCode;
+use qrcode::render::svg;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Serialise Options as `null` and maps as plain objects
+const SERIALIZER: serde_wasm_bindgen::Serializer =
+    serde_wasm_bindgen::Serializer::json_compatible();
+
+fn js_error(err: &QRError) -> JsValue {
+    let error = js_sys::Error::new(&err.to_string());
+    error.set_name("QRError");
+    // Setting a property on a fresh Error object cannot fail
+    let _ = js_sys::Reflect::set(&error, &"code".into(), &err.code().into());
+    error.into()
+}
+
+/// Read a plain object through JSON, so unknown fields are rejected
+fn from_js<T: serde::de::DeserializeOwned>(value: JsValue, what: &str) -> Result<T, JsValue> {
+    let invalid = |message: String| {
+        js_error(&QRError::InvalidFormat {
+            message: format!("invalid {what}: {message}"),
+        })
+    };
+
+    let json = js_sys::JSON::stringify(&value)
+        .ok()
+        .and_then(|json| json.as_string())
+        .ok_or_else(|| invalid("not a JSON object".to_string()))?;
+    serde_json::from_str(&json).map_err(|e| invalid(e.to_string()))
+}
+
+fn to_js(value: &impl Serialize) -> Result<JsValue, JsValue> {
+    value
+        .serialize(&SERIALIZER)
+        .map_err(|e| JsValue::from(js_sys::Error::new(&e.to_string())))
+}
+
+/// Builder for QR payment payloads
+#[wasm_bindgen(js_name = QRBuilder)]
+pub struct JsQRBuilder {
+    inner: QRBuilder,
+}
+
+impl JsQRBuilder {
+    fn set(&mut self, setter: impl FnOnce(QRBuilder) -> QRBuilder) {
+        self.inner = setter(std::mem::take(&mut self.inner));
+    }
+}
+
+#[wasm_bindgen(js_class = QRBuilder)]
+impl JsQRBuilder {
+    /// Create a builder, optionally from a plain object of builder fields
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: JsValue) -> Result<JsQRBuilder, JsValue> {
+        let inner = if config.is_undefined() || config.is_null() {
+            QRBuilder::new()
+        } else {
+            from_js(config, "builder")?
+        };
+        Ok(Self { inner })
+    }
+
+    #[wasm_bindgen(js_name = setMerchantName)]
+    pub fn set_merchant_name(&mut self, name: &str) {
+        self.set(|b| b.merchant_name(name));
+    }
+
+    #[wasm_bindgen(js_name = setMerchantCity)]
+    pub fn set_merchant_city(&mut self, city: &str) {
+        self.set(|b| b.merchant_city(city));
+    }
+
+    #[wasm_bindgen(js_name = setMerchantCategoryCode)]
+    pub fn set_merchant_category_code(&mut self, code: &str) {
+        self.set(|b| b.merchant_category_code(code));
+    }
+
+    #[wasm_bindgen(js_name = setTransactionAmount)]
+    pub fn set_transaction_amount(&mut self, amount: &str) {
+        self.set(|b| b.transaction_amount(amount));
+    }
+
+    #[wasm_bindgen(js_name = setTransactionContext)]
+    pub fn set_transaction_context(&mut self, context: &str) {
+        self.set(|b| b.transaction_context(context));
+    }
+
+    /// Add a scheme such as `{ scheme: "visa", account_info: "..." }`
+    #[wasm_bindgen(js_name = addScheme)]
+    pub fn add_scheme(&mut self, scheme: JsValue) -> Result<(), JsValue> {
+        let scheme: SchemeConfig = from_js(scheme, "scheme")?;
+        self.set(|b| b.add_scheme(scheme));
+        Ok(())
+    }
+
+    /// Set additional data such as `{ bill_number: "INV-001" }`
+    #[wasm_bindgen(js_name = setAdditionalData)]
+    pub fn set_additional_data(&mut self, data: JsValue) -> Result<(), JsValue> {
+        let data: AdditionalData = from_js(data, "additional data")?;
+        self.set(|b| b.additional_data(data));
+        Ok(())
+    }
+
+    /// Set merchant information in an alternate language
+    #[wasm_bindgen(js_name = setLanguageTemplate)]
+    pub fn set_language_template(&mut self, template: JsValue) -> Result<(), JsValue> {
+        let template: LanguageTemplate = from_js(template, "language template")?;
+        self.set(|b| b.language_template(template));
+        Ok(())
+    }
+
+    /// Build the payload string
+    #[wasm_bindgen(js_name = buildPayload)]
+    pub fn build_payload(&self) -> Result<String, JsValue> {
+        self.inner.build_payload().map_err(|e| js_error(&e))
+    }
+
+    /// Build the QR code as SVG markup
+    #[wasm_bindgen(js_name = buildSvg)]
+    pub fn build_svg(&self, size: u32) -> Result<String, JsValue> {
+        self.inner.build_svg(size).map_err(|e| js_error(&e))
+    }
+
+    /// Builder fields as a plain object
+    #[wasm_bindgen(js_name = toObject)]
+    pub fn to_object(&self) -> Result<JsValue, JsValue> {
+        to_js(&self.inner)
+    }
+}
+
+/// Parse and verify a payload into a plain object
+#[wasm_bindgen]
+pub fn parse(payload: &str) -> Result<JsValue, JsValue> {
+    #[derive(Serialize)]
+    struct Parsed {
+        #[serde(flatten)]
+        parsed: parser::ParsedQR,
+        dynamic: bool,
+    }
+
+    let parsed = parser::parse(payload).map_err(|e| js_error(&e))?;
+    to_js(&Parsed {
+        dynamic: parsed.is_dynamic(),
+        parsed,
+    })
+}
+
+/// Validate a payload, returning `{ valid, issues }`
+#[wasm_bindgen]
+pub fn validate(payload: &str) -> Result<JsValue, JsValue> {
+    #[derive(Serialize)]
+    struct Report {
+        valid: bool,
+        issues: Vec<Issue>,
+    }
+
+    let report = validation::validate(payload);
+    to_js(&Report {
+        valid: report.is_valid(),
+        issues: report.issues,
+    })
+}
+
+/// Check the CRC at the end of a payload
+#[wasm_bindgen(js_name = verifyCrc)]
+pub fn verify_crc(payload: &str) -> bool {
+    crc::verify_crc(payload)
+}
+
+/// Render an existing payload as SVG markup
+#[wasm_bindgen(js_name = renderSvg)]
+pub fn render_svg(payload: &str, size: u32) -> Result<String, JsValue> {
+    if payload.len() > constants::MAX_QR_LENGTH {
+        return Err(js_error(&QRError::PayloadTooLong {
+            length: payload.len(),
+        }));
+    }
+
+    let code = QrCode::new(payload).map_err(|e| js_error(&e.into()))?;
+    Ok(code
+        .render::<svg::Color>()
+        .min_dimensions(size, size)
+        .build())
+}
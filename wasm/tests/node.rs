@@ -0,0 +1,86 @@
+#![cfg(target_arch = "wasm32")]
+
+use ethqr_wasm::{JsQRBuilder as QRBuilder, parse, render_svg, validate, verify_crc};
+use js_sys::{Array, JSON, Reflect};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn object(json: &str) -> JsValue {
+    JSON::parse(json).unwrap()
+}
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+    Reflect::get(value, &key.into()).unwrap()
+}
+
+fn builder() -> QRBuilder {
+    QRBuilder::new(object(
+        r#"{
+            "merchant_name": "Restaurant",
+            "merchant_city": "Dire Dawa",
+            "merchant_category_code": "5812",
+            "schemes": [{ "scheme": "visa", "account_info": "4111111111111111" }]
+        }"#,
+    ))
+    .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn builds_and_parses() {
+    let mut builder = builder();
+    builder.set_transaction_amount("50.00");
+    builder
+        .set_additional_data(object(r#"{ "bill_number": "INV-001" }"#))
+        .unwrap();
+    builder
+        .add_scheme(object(
+            r#"{ "scheme": "ips_et", "guid": "581b314e257f41bfbbdc6384daa31d16",
+                 "bic": "CBETETAA", "account": "10000171234567890" }"#,
+        ))
+        .unwrap();
+
+    let payload = builder.build_payload().unwrap();
+    assert!(verify_crc(&payload));
+
+    let parsed = parse(&payload).unwrap();
+    assert_eq!(get(&parsed, "merchant_name"), "Restaurant");
+    assert_eq!(get(&parsed, "dynamic"), true);
+    assert_eq!(Array::from(&get(&parsed, "schemes")).length(), 2);
+    assert_eq!(
+        get(&get(&parsed, "additional_data"), "bill_number"),
+        "INV-001"
+    );
+
+    let report = validate(&payload).unwrap();
+    assert_eq!(get(&report, "valid"), true);
+}
+
+#[wasm_bindgen_test]
+fn renders_svg() {
+    let svg = builder().build_svg(128).unwrap();
+    assert!(svg.contains("<svg"));
+
+    let payload = builder().build_payload().unwrap();
+    assert_eq!(render_svg(&payload, 128).unwrap(), svg);
+}
+
+#[wasm_bindgen_test]
+fn errors_carry_codes() {
+    let mut builder = builder();
+    builder.set_merchant_name("A merchant name that is far too long");
+    let error = builder.build_payload().unwrap_err();
+    assert_eq!(get(&error, "code"), "value_too_long");
+    assert_eq!(get(&error, "name"), "QRError");
+
+    let error = parse("000201").unwrap_err();
+    assert_eq!(get(&error, "code"), "invalid_crc");
+
+    let error = QRBuilder::new(object(r#"{ "merchant": "x" }"#))
+        .err()
+        .unwrap();
+    assert_eq!(get(&error, "code"), "invalid_format");
+
+    let report = validate("000201").unwrap();
+    assert_eq!(get(&report, "valid"), false);
+    assert!(Array::from(&get(&report, "issues")).length() > 0);
+}